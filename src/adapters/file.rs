@@ -0,0 +1,135 @@
+use std::path::{
+    Component,
+    Path,
+    PathBuf
+};
+
+use bytes::Bytes;
+use futures::StreamExt;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use crate::adapters::BodyStream;
+use crate::error::Error;
+
+/// Path of file within directory (names escaping directory are rejected)
+fn to_path(directory: &str, filename: &str) -> Result<PathBuf, Error> {
+    let name = Path::new(filename);
+    let escapes = name.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes || filename.is_empty() {
+        return Err(Error::file(filename, "name must be a relative path within collection"));
+    }
+    Ok(Path::new(directory).join(name))
+}
+
+/// Write body stream to file within directory (creating subdirectories)
+///
+/// Body is written to a temporary file beside the target and renamed on completion so readers never see
+/// partial files
+pub async fn write_file<'a>(
+    directory: &'a str,
+    filename: &'a str,
+    body: BodyStream
+) -> Result<PathBuf, Error> {
+    let path = to_path(directory, filename)?;
+    let display = path.to_string_lossy().into_owned();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await.map_err(|e| Error::file(&display, e))?;
+    }
+
+    let mut temporary = path.clone().into_os_string();
+    temporary.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let temporary = PathBuf::from(temporary);
+
+    let written = write(&temporary, body).await;
+    let renamed = match written {
+        Ok(()) => fs::rename(&temporary, &path).await,
+        Err(e) => Err(e)
+    };
+    if let Err(e) = renamed {
+        let _ = fs::remove_file(&temporary).await;
+        return Err(Error::file(&display, e));
+    }
+    Ok(path)
+}
+
+/// Size of file within directory or None when file doesn't exist
+pub async fn stat_file<'a>(directory: &'a str, filename: &'a str) -> Result<Option<u64>, Error> {
+    let path = to_path(directory, filename)?;
+    match fs::metadata(&path).await {
+        Ok(metadata) => Ok(Some(metadata.len())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Error::file(&path.to_string_lossy(), e))
+    }
+}
+
+/// Read file within directory or None when file doesn't exist
+pub async fn read_file<'a>(directory: &'a str, filename: &'a str) -> Result<Option<Bytes>, Error> {
+    let path = to_path(directory, filename)?;
+    match fs::read(&path).await {
+        Ok(bytes) => Ok(Some(Bytes::from(bytes))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Error::file(&path.to_string_lossy(), e))
+    }
+}
+
+/// Delete file within directory (deleting a file that doesn't exist succeeds, as with S3)
+pub async fn delete_file<'a>(directory: &'a str, filename: &'a str) -> Result<(), Error> {
+    let path = to_path(directory, filename)?;
+    match fs::remove_file(&path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(Error::file(&path.to_string_lossy(), e))
+    }
+}
+
+async fn write(path: &Path, mut body: BodyStream) -> std::io::Result<()> {
+    let mut file = fs::File::create(path).await?;
+    while let Some(bytes) = body.next().await {
+        file.write_all(&bytes?).await?;
+    }
+    file.sync_all().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::stream;
+
+    use crate::adapters::temp_directory;
+
+    #[test]
+    fn to_path_within_directory() {
+        let expect = PathBuf::from("/mnt/efs/data/2021/key.json");
+        let actual = to_path("/mnt/efs", "data/2021/key.json").unwrap();
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn to_path_escaping_directory() {
+        assert!(to_path("/mnt/efs", "../key").is_err());
+        assert!(to_path("/mnt/efs", "/etc/key").is_err());
+        assert!(to_path("/mnt/efs", "").is_err());
+    }
+
+    #[tokio::test]
+    async fn write_file_creates_subdirectories() {
+        let temp = temp_directory();
+        let directory = temp.path();
+        let body: BodyStream = Box::new(stream::iter(vec![Ok(Bytes::from("hello ")), Ok(Bytes::from("world"))]));
+
+        let path = write_file(directory, "a/b/key.txt", body).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello world");
+        assert_eq!(std::fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+        assert_eq!(stat_file(directory, "a/b/key.txt").await.unwrap(), Some(11));
+        assert_eq!(read_file(directory, "a/b/key.txt").await.unwrap(), Some(Bytes::from("hello world")));
+
+        delete_file(directory, "a/b/key.txt").await.unwrap();
+        assert_eq!(stat_file(directory, "a/b/key.txt").await.unwrap(), None);
+        assert_eq!(read_file(directory, "a/b/key.txt").await.unwrap(), None);
+        delete_file(directory, "a/b/key.txt").await.unwrap();
+    }
+}
@@ -1,44 +1,49 @@
-use std::io::{
-    Error,
-    ErrorKind
+use std::io;
+use futures::stream::TryStreamExt;
+use http::{
+    Method,
+    Request
 };
-use futures::stream::{StreamExt, TryStreamExt};
-use http::Request;
 use hyper::{
     Client,
-    header::HeaderValue,
     HeaderMap,
-    Body
+    Body,
+    StatusCode
 };
 use hyper_tls::HttpsConnector;
-use bytes::{BytesMut, BufMut};
 
 use crate::adapters::BodyStream;
+use crate::error::Error;
 
 type Headers = Vec<(String, String)>;
 
-pub async fn get_stream(headers: &Headers, uri: &str) -> (HeaderMap, BodyStream) {
+/// Response status, headers and body stream
+pub type Response = (StatusCode, HeaderMap, BodyStream);
+
+pub async fn get_stream(
+    method: &str,
+    headers: &Headers,
+    uri: &str,
+    body: Option<String>
+) -> Result<Response, Error> {
     let https = HttpsConnector::new();
     let client = Client::builder().build::<_, Body>(https);
 
-    let mut builder = Request::get(uri);
+    let method = Method::from_bytes(method.to_uppercase().as_bytes()).map_err(Error::source)?;
+    let mut builder = Request::builder().method(method).uri(uri);
     for (header, value) in headers {
         builder = builder.header(header, value);
     }
-    let request = builder.body(Body::empty()).unwrap();
-
-    let response = client.request(request).await.unwrap();
-    let mut headers = response.headers().clone();
-    let mut body = response.into_body();
+    let body = match body {
+        Some(body) => Body::from(body),
+        _ => Body::empty()
+    };
+    let request = builder.body(body).map_err(Error::source)?;
 
-    if !headers.contains_key("content-length") {
-        let mut bytes = BytesMut::new();
-        while let Some(next) = body.next().await {
-            bytes.put(next.unwrap());
-        }
-        headers.insert("content-length", HeaderValue::from(bytes.len()));
-        body = Body::from(bytes.freeze());
-    }
+    let response = client.request(request).await.map_err(Error::transport)?;
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.into_body();
 
-    (headers, Box::new(body.map_err(|e| Error::new(ErrorKind::Other, e))))
-}
\ No newline at end of file
+    Ok((status, headers, Box::new(body.map_err(|e| io::Error::new(io::ErrorKind::Other, e)))))
+}
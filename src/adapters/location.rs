@@ -0,0 +1,73 @@
+use std::fmt;
+
+use bytes::Bytes;
+
+use crate::adapters::{
+    file::{
+        delete_file,
+        read_file,
+        write_file
+    },
+    s3::{
+        delete_object,
+        get_object,
+        put_object,
+        to_region
+    },
+    to_stream
+};
+use crate::error::Error;
+use crate::event::{
+    Kind,
+    Location,
+    ObjectOptions
+};
+
+/// Error of object (or file) at location of the matching kind (e.g. invalid content)
+pub fn error<E: fmt::Display>(location: &Location, error: E) -> Error {
+    match location.kind {
+        Kind::S3 => Error::Destination {
+            message: format!("{}/{}: {}", location.collection, location.name, error),
+            code: None,
+            status: None
+        },
+        Kind::File => Error::file(&location.name, error)
+    }
+}
+
+/// Read object (or file) at location or None when it doesn't exist
+pub async fn read(location: &Location) -> Result<Option<Bytes>, Error> {
+    match location.kind {
+        Kind::S3 => {
+            let region = to_region(&location.region, location.endpoint.as_deref())?;
+            get_object(&region, &location.collection, &location.name).await
+        }
+        Kind::File => read_file(&location.collection, &location.name).await
+    }
+}
+
+/// Write object (or file) at location
+pub async fn write(location: &Location, content_type: &str, bytes: Bytes) -> Result<(), Error> {
+    let length = Some(bytes.len() as i64);
+    match location.kind {
+        Kind::S3 => {
+            let region = to_region(&location.region, location.endpoint.as_deref())?;
+            let options = ObjectOptions::default();
+            let body = to_stream(bytes);
+            put_object(&region, &location.collection, &location.name, content_type, length, &options, body).await?;
+        }
+        Kind::File => { write_file(&location.collection, &location.name, to_stream(bytes)).await?; }
+    }
+    Ok(())
+}
+
+/// Delete object (or file) at location
+pub async fn delete(location: &Location) -> Result<(), Error> {
+    match location.kind {
+        Kind::S3 => {
+            let region = to_region(&location.region, location.endpoint.as_deref())?;
+            delete_object(&region, &location.collection, &location.name).await
+        }
+        Kind::File => delete_file(&location.collection, &location.name).await
+    }
+}
@@ -0,0 +1,135 @@
+use std::io;
+use std::pin::Pin;
+use std::sync::{
+    Arc,
+    Mutex
+};
+use std::task::{
+    Context,
+    Poll
+};
+
+use bytes::Bytes;
+use futures::Stream;
+use sha2::{
+    Digest,
+    Sha256
+};
+
+use crate::adapters::BodyStream;
+
+/// Measurement of a consumed body stream (hashes are lowercase hex)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub bytes: i64,
+    pub md5: Option<String>,
+    pub sha256: Option<String>
+}
+
+struct State {
+    bytes: i64,
+    md5: Option<md5::Context>,
+    sha256: Option<Sha256>
+}
+
+/// Body stream counting (and optionally hashing) bytes as they are read
+struct Measured {
+    body: BodyStream,
+    state: Arc<Mutex<State>>
+}
+
+impl Stream for Measured {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let next = Pin::new(&mut self.body).poll_next(cx);
+        if let Poll::Ready(Some(Ok(bytes))) = &next {
+            let mut state = self.state.lock().unwrap();
+            state.bytes += bytes.len() as i64;
+            if let Some(md5) = state.md5.as_mut() { md5.consume(bytes); }
+            if let Some(sha256) = state.sha256.as_mut() { sha256.update(bytes); }
+        }
+        next
+    }
+}
+
+/// Meter reads measurement of a measured body stream once it has been consumed
+pub struct Meter {
+    state: Arc<Mutex<State>>
+}
+
+impl Meter {
+    /// Measurement of bytes read so far (hashes are finalized and not available again)
+    pub fn finish(&self) -> Measurement {
+        let mut state = self.state.lock().unwrap();
+        Measurement {
+            bytes: state.bytes,
+            md5: state.md5.take().map(|md5| format!("{:x}", md5.compute())),
+            sha256: state.sha256.take().map(|sha256| format!("{:x}", sha256.finalize()))
+        }
+    }
+}
+
+/// SHA-256 of buffered bytes (lowercase hex)
+pub fn sha256(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Wrap body stream to count bytes (and hash with MD5 and SHA-256 when `hash` is set)
+pub fn measure(body: BodyStream, hash: bool) -> (BodyStream, Meter) {
+    let state = Arc::new(Mutex::new(State {
+        bytes: 0,
+        md5: if hash { Some(md5::Context::new()) } else { None },
+        sha256: if hash { Some(Sha256::new()) } else { None }
+    }));
+    let measured = Measured { body, state: state.clone() };
+    (Box::new(measured), Meter { state })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::{
+        stream,
+        StreamExt
+    };
+
+    fn body() -> BodyStream {
+        Box::new(stream::iter(vec![Ok(Bytes::from("hello ")), Ok(Bytes::from("world"))]))
+    }
+
+    #[tokio::test]
+    async fn measure_counts_and_hashes() {
+        let (mut body, meter) = measure(body(), true);
+        while body.next().await.is_some() {}
+
+        let expect = Measurement {
+            bytes: 11,
+            md5: Some(String::from("5eb63bbbe01eeed093cb22bb8f5acdc3")),
+            sha256: Some(String::from("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"))
+        };
+        let actual = meter.finish();
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn sha256_hex() {
+        let expect = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        let actual = sha256(b"hello world");
+
+        assert_eq!(actual, expect);
+    }
+
+    #[tokio::test]
+    async fn measure_counts_sans_hash() {
+        let (mut body, meter) = measure(body(), false);
+        while body.next().await.is_some() {}
+
+        let expect = Measurement { bytes: 11, md5: None, sha256: None };
+        let actual = meter.finish();
+
+        assert_eq!(actual, expect);
+    }
+}
@@ -1,18 +1,56 @@
+pub mod file;
 pub mod http;
+pub mod location;
+pub mod measure;
 pub mod s3;
 pub mod secrets;
 
 use std::io;
 
-use bytes::Bytes;
-use futures::Stream;
+use bytes::{
+    Bytes,
+    BytesMut
+};
+use futures::{
+    stream,
+    Stream,
+    StreamExt
+};
 use serde_json::{
     map::Map,
     Value
 };
 
 type QueryParams = Map<String, Value>;
-type BodyStream = Box<dyn Stream<Item = io::Result<Bytes>> + Send + Sync + Unpin>;
+pub type BodyStream = Box<dyn Stream<Item = io::Result<Bytes>> + Send + Sync + Unpin>;
+
+/// Buffer body stream into memory (used when response body must be inspected)
+pub async fn buffer(mut body: BodyStream) -> io::Result<Bytes> {
+    let mut bytes = BytesMut::new();
+    while let Some(next) = body.next().await {
+        bytes.extend_from_slice(&next?);
+    }
+    Ok(bytes.freeze())
+}
+
+/// Buffer body stream into memory up to `limit` bytes
+///
+/// Returns the buffered bytes and, when the body is larger than `limit`, the rest of the body stream
+pub async fn buffer_limited(mut body: BodyStream, limit: usize) -> io::Result<(Bytes, Option<BodyStream>)> {
+    let mut bytes = BytesMut::new();
+    while bytes.len() <= limit {
+        match body.next().await {
+            Some(next) => bytes.extend_from_slice(&next?),
+            _ => return Ok((bytes.freeze(), None))
+        }
+    }
+    Ok((bytes.freeze(), Some(body)))
+}
+
+/// Body stream from buffered bytes
+pub fn to_stream(bytes: Bytes) -> BodyStream {
+    Box::new(stream::iter(vec![Ok(bytes)]))
+}
 
 /// Construct Query from Params Map
 fn to_query(params: &QueryParams) -> String {
@@ -62,11 +100,7 @@ pub fn to_uri<'a>(
 
     uri.push('/');
     if let Some(path) = path {
-        if path.chars().next().unwrap() == '/' {
-            uri.push_str(&path[1..]);
-        } else {
-            uri.push_str(path);
-        }
+        uri.push_str(path.strip_prefix('/').unwrap_or(path));
     }
 
     if let Some(params) = params {
@@ -80,10 +114,44 @@ pub fn to_uri<'a>(
     uri
 }
 
+/// Temporary directory of file adapter tests (removed when dropped)
+#[cfg(test)]
+pub struct TempDirectory(std::path::PathBuf);
+
+#[cfg(test)]
+impl TempDirectory {
+    pub fn path(&self) -> &str { self.0.to_str().unwrap() }
+}
+
+#[cfg(test)]
+impl Drop for TempDirectory {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+pub fn temp_directory() -> TempDirectory {
+    TempDirectory(std::env::temp_dir().join(format!("pac-man-{}", uuid::Uuid::new_v4())))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn buffer_limited_returns_rest() {
+        let body = || -> BodyStream { Box::new(stream::iter(vec![Ok(Bytes::from("abc")), Ok(Bytes::from("def"))])) };
+
+        let (bytes, rest) = buffer_limited(body(), 6).await.unwrap();
+        assert_eq!(&bytes[..], b"abcdef");
+        assert!(rest.is_none());
+
+        let (bytes, rest) = buffer_limited(body(), 2).await.unwrap();
+        assert_eq!(&bytes[..], b"abc");
+        assert_eq!(&buffer(rest.unwrap()).await.unwrap()[..], b"def");
+    }
+
     #[test]
     fn to_query_empty() {
         let params = Map::new();
@@ -127,6 +195,13 @@ mod tests {
         assert_eq!(actual, expect);
     }
 
+    #[test]
+    fn to_uri_empty_path() {
+        let expect = String::from("https://host.name.com/");
+        let actual = to_uri("https", None, "host.name.com", None, Some(""), None, None);
+        assert_eq!(actual, expect);
+    }
+
     #[test]
     fn to_uri_complex() {
         let scheme = "https";
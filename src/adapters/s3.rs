@@ -1,6 +1,23 @@
+use std::collections::{
+    BTreeMap,
+    HashMap
+};
 use std::str::FromStr;
 
-use rusoto_core::Region;
+use bytes::{
+    Bytes,
+    BytesMut
+};
+use futures::StreamExt;
+use log::warn;
+use percent_encoding::{
+    utf8_percent_encode,
+    NON_ALPHANUMERIC
+};
+use rusoto_core::{
+    Region,
+    RusotoError
+};
 use rusoto_s3::{
     S3,
     S3Client,
@@ -8,42 +25,327 @@ use rusoto_s3::{
     PutObjectOutput,
     HeadObjectRequest,
     HeadObjectOutput,
+    GetObjectRequest,
+    GetObjectOutput,
+    GetObjectError,
+    DeleteObjectRequest,
+    CreateMultipartUploadRequest,
+    UploadPartRequest,
+    CompletedPart,
+    CompletedMultipartUpload,
+    CompleteMultipartUploadRequest,
+    AbortMultipartUploadRequest,
     StreamingBody
 };
 
-use crate::adapters::BodyStream;
+use crate::adapters::{
+    BodyStream,
+    buffer,
+    to_stream
+};
+use crate::error::Error;
+use crate::event::ObjectOptions;
+
+/// AWS region or custom region for S3-compatible endpoints (e.g. MinIO, LocalStack)
+///
+/// Requests always use path-style addressing (`<endpoint>/<bucket>/<key>`)
+pub fn to_region(region: &str, endpoint: Option<&str>) -> Result<Region, Error> {
+    if let Some(endpoint) = endpoint {
+        let name = if region.is_empty() { "us-east-1" } else { region };
+        return Ok(Region::Custom {
+            name: String::from(name),
+            endpoint: String::from(endpoint.trim_end_matches('/'))
+        });
+    }
+    Region::from_str(region).map_err(|e| Error::Destination {
+        message: e.to_string(),
+        code: None,
+        status: None
+    })
+}
+
+/// Object tags as URL encoded query (`key=value&key=value`)
+fn to_tagging(tags: &BTreeMap<String, String>) -> Option<String> {
+    if tags.is_empty() { return None; }
+    let encode = |s: &str| utf8_percent_encode(s, NON_ALPHANUMERIC).to_string();
+    let pairs: Vec<String> = tags
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect();
+    Some(pairs.join("&"))
+}
+
+fn to_metadata(metadata: &BTreeMap<String, String>) -> Option<HashMap<String, String>> {
+    if metadata.is_empty() { return None; }
+    Some(metadata.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+}
 
 pub async fn put_object<'a>(
-    region: &'a str,
+    region: &'a Region,
     bucket: &'a str,
     filename: &'a str,
     content_type: &'a str,
     content_length: Option<i64>,
+    options: &'a ObjectOptions,
     body: BodyStream
-) -> PutObjectOutput {
-    let region = Region::from_str(region).unwrap();
+) -> Result<PutObjectOutput, Error> {
     let stream = StreamingBody::new(body);
-    let client = S3Client::new(region);
+    let client = S3Client::new(region.clone());
     client.put_object(PutObjectRequest {
         bucket: String::from(bucket),
         key: String::from(filename),
         content_type: Some(String::from(content_type)),
         content_length,
         body: Some(stream),
+        server_side_encryption: options.encryption.clone(),
+        ssekms_key_id: options.kms_key_id.clone(),
+        storage_class: options.storage_class.clone(),
+        acl: options.acl.clone(),
+        cache_control: options.cache_control.clone(),
+        content_encoding: options.content_encoding.clone(),
+        tagging: to_tagging(&options.tags),
+        metadata: to_metadata(&options.metadata),
+        ..Default::default()
+    }).await.map_err(Error::destination)
+}
+
+/// Head object or None when object doesn't exist
+pub async fn head_object<'a>(
+    region: &'a Region,
+    bucket: &'a str,
+    filename: &'a str
+) -> Result<Option<HeadObjectOutput>, Error> {
+    let client = S3Client::new(region.clone());
+    let output = client.head_object(HeadObjectRequest {
+        bucket: String::from(bucket),
+        key: String::from(filename),
         ..Default::default()
-    }).await.unwrap()
+    }).await.map_err(Error::destination);
+    match output {
+        Ok(output) => Ok(Some(output)),
+        Err(Error::Destination { status: Some(404), .. }) => Ok(None),
+        Err(e) => Err(e)
+    }
 }
 
-pub async fn get_object_head<'a>(
-    region: &'a str,
+/// Get object body or None when object doesn't exist
+pub async fn get_object<'a>(
+    region: &'a Region,
+    bucket: &'a str,
+    filename: &'a str
+) -> Result<Option<Bytes>, Error> {
+    let client = S3Client::new(region.clone());
+    let output = client.get_object(GetObjectRequest {
+        bucket: String::from(bucket),
+        key: String::from(filename),
+        ..Default::default()
+    }).await;
+    to_object(output).await
+}
+
+/// Read body of get object output or None when object doesn't exist
+///
+/// S3 reports a missing key as a `NoSuchKey` service error (parsed from the error body) rather than
+/// an unknown 404 response, so both are matched.
+pub async fn to_object(output: Result<GetObjectOutput, RusotoError<GetObjectError>>) -> Result<Option<Bytes>, Error> {
+    let body = match output {
+        Ok(output) => output.body,
+        Err(RusotoError::Service(GetObjectError::NoSuchKey(_))) => return Ok(None),
+        Err(e) => match Error::destination(e) {
+            Error::Destination { status: Some(404), .. } => return Ok(None),
+            e => return Err(e)
+        }
+    };
+    match body {
+        Some(body) => Ok(Some(buffer(Box::new(body)).await.map_err(Error::source)?)),
+        _ => Ok(Some(Bytes::new()))
+    }
+}
+
+pub async fn delete_object<'a>(
+    region: &'a Region,
+    bucket: &'a str,
+    filename: &'a str
+) -> Result<(), Error> {
+    let client = S3Client::new(region.clone());
+    client.delete_object(DeleteObjectRequest {
+        bucket: String::from(bucket),
+        key: String::from(filename),
+        ..Default::default()
+    }).await.map_err(Error::destination)?;
+    Ok(())
+}
+
+/// Upload body stream returning ETag of object
+///
+/// Bodies of known length up to `part_size` bytes are put directly. Bodies of unknown length or larger
+/// than `part_size` are read a part at a time using multipart upload (memory is bound by `part_size`).
+#[allow(clippy::too_many_arguments)]
+pub async fn upload<'a>(
+    region: &'a Region,
     bucket: &'a str,
     filename: &'a str,
-) -> HeadObjectOutput {
-    let region = Region::from_str(region).unwrap();
-    let client = S3Client::new(region);
-    client.head_object(HeadObjectRequest {
+    content_type: &'a str,
+    content_length: Option<i64>,
+    options: &'a ObjectOptions,
+    mut body: BodyStream,
+    part_size: usize
+) -> Result<Option<String>, Error> {
+    if let Some(length) = content_length {
+        if length <= part_size as i64 {
+            let output = put_object(region, bucket, filename, content_type, content_length, options, body).await?;
+            return Ok(output.e_tag);
+        }
+    }
+
+    // body smaller than a part is put once read
+    let first = read_part(&mut body, part_size).await?;
+    if first.len() < part_size {
+        let length = Some(first.len() as i64);
+        let body = to_stream(first.freeze());
+        let output = put_object(region, bucket, filename, content_type, length, options, body).await?;
+        return Ok(output.e_tag);
+    }
+
+    let client = S3Client::new(region.clone());
+    let upload = client.create_multipart_upload(CreateMultipartUploadRequest {
         bucket: String::from(bucket),
         key: String::from(filename),
+        content_type: Some(String::from(content_type)),
+        server_side_encryption: options.encryption.clone(),
+        ssekms_key_id: options.kms_key_id.clone(),
+        storage_class: options.storage_class.clone(),
+        acl: options.acl.clone(),
+        cache_control: options.cache_control.clone(),
+        content_encoding: options.content_encoding.clone(),
+        tagging: to_tagging(&options.tags),
+        metadata: to_metadata(&options.metadata),
         ..Default::default()
-    }).await.unwrap()
-}
\ No newline at end of file
+    }).await.map_err(Error::destination)?;
+    let upload_id = upload.upload_id.ok_or_else(|| Error::Destination {
+        message: String::from("multipart upload id missing"),
+        code: None,
+        status: None
+    })?;
+
+    let uploaded = upload_parts(&client, bucket, filename, &upload_id, first, body, part_size).await;
+    if uploaded.is_err() {
+        // abort so parts aren't stored (and billed) indefinitely
+        let aborted = client.abort_multipart_upload(AbortMultipartUploadRequest {
+            bucket: String::from(bucket),
+            key: String::from(filename),
+            upload_id: upload_id.clone(),
+            ..Default::default()
+        }).await;
+        if let Err(e) = aborted {
+            warn!("failed to abort multipart upload {} of {}: {}", upload_id, filename, Error::destination(e));
+        }
+    }
+    uploaded
+}
+
+/// Upload parts of body stream (starting with first part) and complete multipart upload
+async fn upload_parts(
+    client: &S3Client,
+    bucket: &str,
+    filename: &str,
+    upload_id: &str,
+    first: BytesMut,
+    mut body: BodyStream,
+    part_size: usize
+) -> Result<Option<String>, Error> {
+    let mut parts = vec![];
+    let mut part = first;
+    while !part.is_empty() {
+        let part_number = parts.len() as i64 + 1;
+        let output = client.upload_part(UploadPartRequest {
+            bucket: String::from(bucket),
+            key: String::from(filename),
+            upload_id: String::from(upload_id),
+            part_number,
+            content_length: Some(part.len() as i64),
+            body: Some(StreamingBody::new(to_stream(part.freeze()))),
+            ..Default::default()
+        }).await.map_err(Error::destination)?;
+        parts.push(CompletedPart { e_tag: output.e_tag, part_number: Some(part_number) });
+        part = read_part(&mut body, part_size).await?;
+    }
+
+    let output = client.complete_multipart_upload(CompleteMultipartUploadRequest {
+        bucket: String::from(bucket),
+        key: String::from(filename),
+        upload_id: String::from(upload_id),
+        multipart_upload: Some(CompletedMultipartUpload { parts: Some(parts) }),
+        ..Default::default()
+    }).await.map_err(Error::destination)?;
+    Ok(output.e_tag)
+}
+
+/// Read body stream until part size is reached or stream ends
+async fn read_part(body: &mut BodyStream, part_size: usize) -> Result<BytesMut, Error> {
+    let mut part = BytesMut::new();
+    while part.len() < part_size {
+        match body.next().await {
+            Some(bytes) => part.extend_from_slice(&bytes.map_err(Error::source)?),
+            _ => break
+        }
+    }
+    Ok(part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use futures::stream;
+
+    #[test]
+    fn to_region_custom_endpoint() {
+        let expect = Region::Custom {
+            name: String::from("us-east-1"),
+            endpoint: String::from("http://localhost:9000")
+        };
+        let actual = to_region("", Some("http://localhost:9000/")).unwrap();
+
+        assert_eq!(actual, expect);
+        assert!(to_region("mars-north-1", None).is_err());
+    }
+
+    fn body() -> BodyStream {
+        let chunks = vec![Ok(Bytes::from("abc")), Ok(Bytes::from("def")), Ok(Bytes::from("gh"))];
+        Box::new(stream::iter(chunks))
+    }
+
+    #[test]
+    fn to_tagging_encodes_tags() {
+        let mut tags = BTreeMap::new();
+        tags.insert(String::from("chunk"), String::from("3"));
+        tags.insert(String::from("source"), String::from("a b&c"));
+
+        let expect = Some(String::from("chunk=3&source=a%20b%26c"));
+        let actual = to_tagging(&tags);
+
+        assert_eq!(actual, expect);
+        assert_eq!(to_tagging(&BTreeMap::new()), None);
+    }
+
+    #[tokio::test]
+    async fn to_object_missing_key() {
+        let missing = Err(RusotoError::Service(GetObjectError::NoSuchKey(String::from("orders.json"))));
+        let output = Ok(GetObjectOutput { body: Some(StreamingBody::from(b"{}".to_vec())), ..Default::default() });
+
+        assert_eq!(to_object(missing).await.unwrap(), None);
+        assert_eq!(to_object(output).await.unwrap(), Some(Bytes::from("{}")));
+        assert!(to_object(Err(RusotoError::Validation(String::from("invalid")))).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn read_part_until_part_size() {
+        let mut body = body();
+
+        assert_eq!(&read_part(&mut body, 5).await.unwrap()[..], b"abcdef");
+        assert_eq!(&read_part(&mut body, 5).await.unwrap()[..], b"gh");
+        assert!(read_part(&mut body, 5).await.unwrap().is_empty());
+    }
+}
@@ -7,17 +7,20 @@ use rusoto_secretsmanager::{
     GetSecretValueRequest
 };
 
+use crate::error::Error;
+
 pub async fn get_secret(
     region: String,
     id: String,
     version_id: Option<String>,
     version_stage: Option<String>
-) -> Option<String> {
-    let region = Region::from_str(&region).unwrap();
+) -> Result<Option<String>, Error> {
+    let region = Region::from_str(&region).map_err(Error::secrets)?;
     let secrets = SecretsManagerClient::new(region);
-    secrets.get_secret_value(GetSecretValueRequest {
-        secret_id: id,
+    let secret = secrets.get_secret_value(GetSecretValueRequest {
+        secret_id: id.clone(),
         version_id,
         version_stage,
-    }).await.unwrap().secret_string
-}
\ No newline at end of file
+    }).await.map_err(|e| Error::secrets(format!("{}: {}", id, e)))?;
+    Ok(secret.secret_string)
+}
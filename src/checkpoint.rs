@@ -0,0 +1,131 @@
+use bytes::Bytes;
+use serde::{
+    Deserialize,
+    Serialize
+};
+use serde_json::Value;
+
+use crate::adapters::location;
+use crate::error::Error;
+use crate::event::{
+    Checkpoint,
+    Location
+};
+use crate::report::ObjectReport;
+use crate::state::Values;
+
+/// Progress of a run persisted after every chunk
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    /// Index of the next chunk
+    pub chunk: u64,
+    /// Chunks stored
+    pub chunks: u64,
+    /// Items seen across pages of the (inner) loop (source `until` total condition)
+    pub items: u64,
+    /// Modifier state by position (see `Modifiers::checkpoint`)
+    pub mods: Value,
+    pub objects: Vec<ObjectReport>,
+    /// Job state values of the last stored chunk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<Values>,
+    /// Run start time (RFC 3339) shared by time-based modifiers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<String>
+}
+
+/// Load checkpoint or None when no run is in progress
+pub async fn load(config: &Checkpoint) -> Result<Option<Progress>, Error> {
+    let bytes = location::read(&config.location).await?;
+    to_progress(&config.location, bytes)
+}
+
+/// Progress of checkpoint body or None when checkpoint doesn't exist (run resumes from nothing)
+fn to_progress(location: &Location, bytes: Option<Bytes>) -> Result<Option<Progress>, Error> {
+    match bytes {
+        Some(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| location::error(location, format!("invalid checkpoint: {}", e))),
+        _ => Ok(None)
+    }
+}
+
+pub async fn save(config: &Checkpoint, progress: &Progress) -> Result<(), Error> {
+    let body = serde_json::to_vec(progress).map_err(|e| location::error(&config.location, e))?;
+    location::write(&config.location, "application/json", body.into()).await
+}
+
+/// Delete checkpoint of a completed run
+pub async fn clear(config: &Checkpoint) -> Result<(), Error> {
+    location::delete(&config.location).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rusoto_core::RusotoError;
+    use rusoto_s3::GetObjectError;
+    use serde_json::json;
+
+    use crate::adapters::{
+        s3::to_object,
+        temp_directory
+    };
+    use crate::event::Kind;
+
+    fn config(directory: &str) -> Checkpoint {
+        Checkpoint {
+            location: Location {
+                kind: Kind::File,
+                region: String::new(),
+                endpoint: None,
+                collection: String::from(directory),
+                name: String::from("orders.json")
+            },
+            resume: true
+        }
+    }
+
+    #[tokio::test]
+    async fn checkpoint_save_load_clear() {
+        let directory = temp_directory();
+        let config = config(directory.path());
+        let progress = Progress {
+            chunk: 143,
+            chunks: 143,
+            mods: json!([{ "name": "chunks", "state": { "chunk_start": 1430, "chunk_end": 1440, "chunk_index": 143 } }]),
+            ..Default::default()
+        };
+
+        assert_eq!(load(&config).await.unwrap(), None);
+        save(&config, &progress).await.unwrap();
+        assert_eq!(load(&config).await.unwrap(), Some(progress));
+        clear(&config).await.unwrap();
+        assert_eq!(load(&config).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn checkpoint_clear_sans_checkpoint() {
+        let directory = temp_directory();
+        let config = config(directory.path());
+
+        assert!(clear(&config).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn checkpoint_load_sans_s3_checkpoint() {
+        let location = Location {
+            kind: Kind::S3,
+            region: String::from("us-east-1"),
+            endpoint: None,
+            collection: String::from("pac-man"),
+            name: String::from("checkpoints/orders.json")
+        };
+        let missing = Err(RusotoError::Service(GetObjectError::NoSuchKey(location.name.clone())));
+
+        let actual = to_progress(&location, to_object(missing).await.unwrap()).unwrap();
+
+        assert_eq!(actual, None);
+    }
+}
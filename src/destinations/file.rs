@@ -0,0 +1,43 @@
+use crate::adapters::{
+    BodyStream,
+    file
+};
+use crate::destinations::{
+    Destination,
+    Stat
+};
+use crate::error::Error;
+use crate::event::ObjectOptions;
+
+/// Directory on local (or mounted) filesystem
+pub struct File {
+    directory: String
+}
+
+impl File {
+    pub fn new(directory: &str) -> Self {
+        File { directory: String::from(directory) }
+    }
+}
+
+#[async_trait::async_trait]
+impl Destination for File {
+    fn collection(&self) -> &str { &self.directory }
+
+    async fn put(
+        &self,
+        name: &str,
+        _: &str,
+        _: Option<i64>,
+        _: &ObjectOptions,
+        body: BodyStream
+    ) -> Result<Option<String>, Error> {
+        file::write_file(&self.directory, name, body).await?;
+        Ok(None)
+    }
+
+    async fn stat(&self, name: &str) -> Result<Option<Stat>, Error> {
+        let bytes = file::stat_file(&self.directory, name).await?;
+        Ok(bytes.map(|bytes| Stat { bytes: Some(bytes as i64), ..Default::default() }))
+    }
+}
@@ -0,0 +1,244 @@
+mod file;
+mod s3;
+
+use std::collections::HashMap;
+use std::io;
+
+use futures::{
+    channel::mpsc,
+    future::join_all,
+    join,
+    SinkExt,
+    StreamExt
+};
+use crate::adapters::{
+    BodyStream,
+    s3::to_region
+};
+use crate::error::Error;
+use crate::event::{
+    Destination as DestinationConfig,
+    Kind,
+    Mode,
+    ObjectOptions
+};
+
+pub type Dest = Box<dyn Destination + Send + Sync>;
+type Dests = Vec<Dest>;
+
+/// Chunks buffered per destination while teeing (slowest destination bounds memory)
+const TEE_BUFFER: usize = 4;
+
+/// User metadata key of object SHA-256 (compared by `if-changed` write mode)
+pub const HASH_METADATA: &str = "pac-man-sha256";
+
+/// Stat of an object (or file) in a destination
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stat {
+    pub bytes: Option<i64>,
+    pub e_tag: Option<String>,
+    pub metadata: HashMap<String, String>
+}
+
+/// Destination is able to store body streams by name
+#[async_trait::async_trait]
+pub trait Destination {
+    /// Collection written to (bucket or directory)
+    fn collection(&self) -> &str;
+
+    /// Bytes buffered per part of large bodies (bounds bodies buffered to hash them)
+    fn part_size(&self) -> Option<usize> { None }
+
+    /// Put body stream returning ETag of object (when known)
+    async fn put(
+        &self,
+        name: &str,
+        content_type: &str,
+        content_length: Option<i64>,
+        options: &ObjectOptions,
+        body: BodyStream
+    ) -> Result<Option<String>, Error>;
+
+    /// Stat object or None when object doesn't exist
+    async fn stat(&self, name: &str) -> Result<Option<Stat>, Error>;
+}
+
+fn load(config: &DestinationConfig) -> Result<Dest, Error> {
+    match config.kind {
+        Kind::S3 => {
+            let region = to_region(&config.region, config.endpoint.as_deref())?;
+            Ok(Box::new(s3::S3::new(region, &config.collection, config.part_size as usize)))
+        }
+        Kind::File => Ok(Box::new(file::File::new(&config.collection)))
+    }
+}
+
+/// Convert event destination configs to destinations
+pub fn to_destinations(config: &[DestinationConfig]) -> Result<Dests, Error> {
+    config.iter().map(load).collect()
+}
+
+/// Object to put in a destination (name and options are reduced)
+#[derive(Clone)]
+pub struct Target<'a> {
+    pub destination: &'a Dest,
+    pub name: String,
+    pub options: ObjectOptions,
+    pub mode: Mode
+}
+
+/// Put body stream to every target returning results (ETags or errors) of the same order
+///
+/// Body stream is teed (not re-read) so each destination receives every chunk. Objects put to other
+/// destinations are kept when a destination fails (deleting them would lose the object they replaced).
+pub async fn put_all(
+    targets: &[Target<'_>],
+    content_type: &str,
+    content_length: Option<i64>,
+    body: BodyStream
+) -> Vec<Result<Option<String>, Error>> {
+    if targets.len() == 1 {
+        let target = &targets[0];
+        return vec![target.destination.put(&target.name, content_type, content_length, &target.options, body).await];
+    }
+
+    let (bodies, pump) = tee(body, targets.len());
+    let puts = targets
+        .iter()
+        .zip(bodies)
+        .map(|(target, body)| {
+            target.destination.put(&target.name, content_type, content_length, &target.options, body)
+        });
+    let (_, results) = join!(pump, join_all(puts));
+    results
+}
+
+/// Split body stream into `count` streams each reading every chunk
+///
+/// Returned future pumps body stream into split streams and must be polled alongside them
+fn tee(mut body: BodyStream, count: usize) -> (Vec<BodyStream>, impl std::future::Future<Output = ()>) {
+    let mut senders = vec![];
+    let mut bodies: Vec<BodyStream> = vec![];
+    for _ in 0..count {
+        let (sender, receiver) = mpsc::channel::<io::Result<bytes::Bytes>>(TEE_BUFFER);
+        senders.push(Some(sender));
+        bodies.push(Box::new(receiver));
+    }
+
+    let pump = async move {
+        while let Some(next) = body.next().await {
+            let failed = next.is_err();
+            for slot in senders.iter_mut() {
+                if let Some(sender) = slot {
+                    let item = match &next {
+                        Ok(bytes) => Ok(bytes.clone()),
+                        Err(e) => Err(io::Error::new(e.kind(), e.to_string()))
+                    };
+                    // destination stopped reading (failed); stop sending to it
+                    if sender.send(item).await.is_err() { *slot = None; }
+                }
+            }
+            if failed || senders.iter().all(Option::is_none) { break; }
+        }
+    };
+    (bodies, pump)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+    use std::sync::{
+        Arc,
+        Mutex
+    };
+
+    use bytes::Bytes;
+    use futures::stream;
+
+    type Objects = Arc<Mutex<BTreeMap<String, String>>>;
+
+    struct DestinationMock {
+        fail: bool,
+        objects: Objects
+    }
+
+    #[async_trait::async_trait]
+    impl Destination for DestinationMock {
+        fn collection(&self) -> &str { "mock" }
+
+        async fn put(
+            &self,
+            name: &str,
+            _: &str,
+            _: Option<i64>,
+            _: &ObjectOptions,
+            mut body: BodyStream
+        ) -> Result<Option<String>, Error> {
+            if self.fail { return Err(Error::file(name, "failed")); }
+            let mut read = String::new();
+            while let Some(bytes) = body.next().await {
+                read.push_str(std::str::from_utf8(&bytes.unwrap()).unwrap());
+            }
+            self.objects.lock().unwrap().insert(String::from(name), read.clone());
+            Ok(Some(read))
+        }
+
+        async fn stat(&self, _: &str) -> Result<Option<Stat>, Error> { Ok(None) }
+    }
+
+    fn destinations(fail: &[bool], objects: &Objects) -> Dests {
+        fail.iter().map(|fail| -> Dest {
+            Box::new(DestinationMock { fail: *fail, objects: objects.clone() })
+        }).collect()
+    }
+
+    fn body() -> BodyStream {
+        let chunks: Vec<io::Result<Bytes>> = (0..10).map(|i| Ok(Bytes::from(format!("{}", i)))).collect();
+        Box::new(stream::iter(chunks))
+    }
+
+    fn targets(destinations: &[Dest]) -> Vec<Target<'_>> {
+        destinations.iter().zip(["a", "b", "c"].iter()).map(|(destination, name)| Target {
+            destination,
+            name: String::from(*name),
+            options: ObjectOptions::default(),
+            mode: Mode::Overwrite
+        }).collect()
+    }
+
+    #[tokio::test]
+    async fn put_all_tees_body() {
+        let objects = Objects::default();
+        let destinations = destinations(&[false, false, false], &objects);
+
+        let expect = vec![Some(String::from("0123456789")); 3];
+        let actual: Vec<Option<String>> = put_all(&targets(&destinations), "text/plain", None, body())
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(actual, expect);
+        assert_eq!(objects.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn put_all_keeps_objects_on_failure() {
+        let objects = Objects::default();
+        for name in ["a", "b", "c"].iter() {
+            objects.lock().unwrap().insert(String::from(*name), String::from("previous"));
+        }
+        let destinations = destinations(&[false, true, false], &objects);
+
+        let actual = put_all(&targets(&destinations), "text/plain", None, body()).await;
+
+        assert!(actual[0].is_ok() && actual[1].is_err() && actual[2].is_ok());
+        let mut expect = BTreeMap::new();
+        expect.insert(String::from("a"), String::from("0123456789"));
+        expect.insert(String::from("b"), String::from("previous"));
+        expect.insert(String::from("c"), String::from("0123456789"));
+        assert_eq!(*objects.lock().unwrap(), expect);
+    }
+}
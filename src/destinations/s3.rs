@@ -0,0 +1,56 @@
+use rusoto_core::Region;
+
+use crate::adapters::{
+    BodyStream,
+    s3
+};
+use crate::destinations::{
+    Destination,
+    Stat
+};
+use crate::error::Error;
+use crate::event::ObjectOptions;
+
+/// S3 bucket in region (bodies larger than part size use multipart upload)
+pub struct S3 {
+    region: Region,
+    bucket: String,
+    part_size: usize
+}
+
+impl S3 {
+    pub fn new(region: Region, bucket: &str, part_size: usize) -> Self {
+        S3 {
+            region,
+            bucket: String::from(bucket),
+            part_size
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Destination for S3 {
+    fn collection(&self) -> &str { &self.bucket }
+
+    fn part_size(&self) -> Option<usize> { Some(self.part_size) }
+
+    async fn put(
+        &self,
+        name: &str,
+        content_type: &str,
+        content_length: Option<i64>,
+        options: &ObjectOptions,
+        body: BodyStream
+    ) -> Result<Option<String>, Error> {
+        s3::upload(&self.region, &self.bucket, name, content_type, content_length, options, body, self.part_size).await
+    }
+
+    async fn stat(&self, name: &str) -> Result<Option<Stat>, Error> {
+        let head = s3::head_object(&self.region, &self.bucket, name).await?;
+        Ok(head.map(|head| Stat {
+            bytes: head.content_length,
+            e_tag: head.e_tag,
+            metadata: head.metadata.unwrap_or_default()
+        }))
+    }
+}
@@ -0,0 +1,312 @@
+use std::error;
+use std::fmt;
+
+use http::StatusCode;
+use regex::Regex;
+use rusoto_core::RusotoError;
+use serde::{
+    Deserialize,
+    Serialize
+};
+use serde_json::{
+    json,
+    Value
+};
+
+use crate::event::ValidationError;
+use crate::report::Report;
+
+/// Error raised by pac-man adapters, modifiers and event validation
+#[derive(Debug)]
+pub enum Error {
+    /// Event failed validation
+    Config(ValidationError),
+    /// Source HTTP request failed (transport is set for connection failures, status for rejected responses)
+    Source {
+        message: String,
+        transport: Option<Transport>,
+        status: Option<u16>
+    },
+    /// Destination S3 operation failed (code is the S3 error code when known)
+    Destination {
+        message: String,
+        code: Option<String>,
+        status: Option<u16>
+    },
+    /// File destination operation failed
+    File {
+        message: String,
+        path: String
+    },
+    /// Secrets Manager lookup failed
+    Secrets {
+        message: String
+    },
+    /// Modifier failed to modify a target
+    Modifier {
+        name: String,
+        message: String
+    }
+}
+
+impl Error {
+    pub fn source<E: fmt::Display>(error: E) -> Self {
+        Error::Source { message: error.to_string(), transport: None, status: None }
+    }
+
+    /// Source error from response status rejected by status policy
+    pub fn status(status: StatusCode) -> Self {
+        Error::Source {
+            message: format!("source responded {}", status),
+            transport: None,
+            status: Some(status.as_u16())
+        }
+    }
+
+    /// Source error from hyper error classified by transport failure
+    pub fn transport(error: hyper::Error) -> Self {
+        let transport = if error.is_connect() {
+            Transport::Connect
+        } else if error.is_timeout() {
+            Transport::Timeout
+        } else if error.is_incomplete_message() {
+            Transport::Incomplete
+        } else if error.is_closed() {
+            Transport::Closed
+        } else {
+            return Error::source(error);
+        };
+        Error::Source { message: error.to_string(), transport: Some(transport), status: None }
+    }
+
+    pub fn file<E: fmt::Display>(path: &str, error: E) -> Self {
+        Error::File { message: error.to_string(), path: String::from(path) }
+    }
+
+    pub fn secrets<E: fmt::Display>(error: E) -> Self {
+        Error::Secrets { message: error.to_string() }
+    }
+
+    pub fn modifier<E: fmt::Display>(name: &str, error: E) -> Self {
+        Error::Modifier { name: String::from(name), message: error.to_string() }
+    }
+
+    /// Destination error from rusoto error
+    ///
+    /// Most S3 failures surface as `RusotoError::Unknown` so the S3 error code is read from the body
+    pub fn destination<E: error::Error + 'static>(error: RusotoError<E>) -> Self {
+        match &error {
+            RusotoError::Unknown(response) => {
+                let body = String::from_utf8_lossy(&response.body);
+                let code = Regex::new(r"<Code>([^<]+)</Code>")
+                    .unwrap()
+                    .captures(&body)
+                    .map(|c| String::from(&c[1]));
+                let message = match &code {
+                    Some(code) => format!("{} ({})", code, response.status),
+                    _ => format!("unexpected response ({})", response.status)
+                };
+                Error::Destination { message, code, status: Some(response.status.as_u16()) }
+            }
+            _ => Error::Destination { message: error.to_string(), code: None, status: None }
+        }
+    }
+
+    /// Error kind used by alerting to tell failures apart
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Config(_) => "config",
+            Error::Source { .. } => "source_http",
+            Error::Destination { .. } => "destination_s3",
+            Error::File { .. } => "destination_file",
+            Error::Secrets { .. } => "secrets",
+            Error::Modifier { .. } => "modifier"
+        }
+    }
+
+    /// Attach run stage and chunk index to error
+    pub fn at(self, stage: Stage, chunk: Option<u64>) -> Failure {
+        Failure { stage, chunk, error: self, report: None }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Config(e) => write!(f, "{}", e),
+            Error::Source { message, .. } => write!(f, "source request failed: {}", message),
+            Error::Destination { message, .. } => write!(f, "destination failed: {}", message),
+            Error::File { message, path } => write!(f, "file destination failed: {}: {}", path, message),
+            Error::Secrets { message } => write!(f, "secrets lookup failed: {}", message),
+            Error::Modifier { name, message } => write!(f, "modifier \"{}\" failed: {}", name, message)
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<ValidationError> for Error {
+    fn from(e: ValidationError) -> Self {
+        Error::Config(e)
+    }
+}
+
+/// Transport failure of a source request (no response received)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    Connect,
+    Closed,
+    Incomplete,
+    Timeout
+}
+
+/// Stage of a run in which an error occurred
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Event,
+    Modifiers,
+    Source,
+    Destination,
+    State,
+    Checkpoint
+}
+
+/// Failure of a run returned to Lambda runtime as a structured error
+///
+/// Displays as JSON so the `errorMessage` of the Lambda response can be parsed by callers
+#[derive(Debug)]
+pub struct Failure {
+    pub stage: Stage,
+    pub chunk: Option<u64>,
+    pub error: Error,
+    pub report: Option<Report>
+}
+
+impl Failure {
+    pub fn to_value(&self) -> Value {
+        let mut value = json!({
+            "kind": self.error.kind(),
+            "stage": self.stage,
+            "chunk": self.chunk,
+            "message": self.error.to_string()
+        });
+        match &self.error {
+            Error::Config(e) => {
+                value["problems"] = e.problems
+                    .iter()
+                    .map(|p| json!({ "path": p.path, "reason": p.reason }))
+                    .collect();
+            }
+            Error::Source { transport, status, .. } => {
+                if let Some(transport) = transport { value["transport"] = json!(transport); }
+                if let Some(status) = status { value["status"] = json!(status); }
+            }
+            Error::Destination { code, status, .. } => {
+                value["code"] = json!(code);
+                value["status"] = json!(status);
+            }
+            Error::File { path, .. } => {
+                value["path"] = json!(path);
+            }
+            Error::Modifier { name, .. } => {
+                value["modifier"] = json!(name);
+            }
+            _ => {}
+        }
+        if let Some(report) = &self.report {
+            value["report"] = json!(report);
+        }
+        value
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_value())
+    }
+}
+
+impl error::Error for Failure {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use http::HeaderMap;
+    use rusoto_core::request::BufferedHttpResponse;
+
+    use crate::event::Problem;
+
+    #[derive(Debug)]
+    struct ServiceError {}
+    impl fmt::Display for ServiceError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "service error") }
+    }
+    impl error::Error for ServiceError {}
+
+    #[test]
+    fn error_destination_reads_s3_code() {
+        let error: RusotoError<ServiceError> = RusotoError::Unknown(BufferedHttpResponse {
+            status: StatusCode::NOT_FOUND,
+            body: Bytes::from("<Error><Code>NoSuchBucket</Code><Message>nope</Message></Error>"),
+            headers: HeaderMap::default()
+        });
+
+        let actual = Error::destination(error);
+
+        match actual {
+            Error::Destination { code, status, .. } => {
+                assert_eq!(code, Some(String::from("NoSuchBucket")));
+                assert_eq!(status, Some(404));
+            }
+            _ => panic!("expected destination error")
+        }
+    }
+
+    #[test]
+    fn failure_to_value_source() {
+        let failure = Error::source("connection reset").at(Stage::Source, Some(3));
+
+        let expect = json!({
+            "kind": "source_http",
+            "stage": "source",
+            "chunk": 3,
+            "message": "source request failed: connection reset"
+        });
+        let actual = failure.to_value();
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn failure_to_value_status() {
+        let failure = Error::status(StatusCode::INTERNAL_SERVER_ERROR).at(Stage::Source, Some(0));
+
+        let actual = failure.to_value();
+
+        assert_eq!(actual["kind"], json!("source_http"));
+        assert_eq!(actual["status"], json!(500));
+        assert_eq!(actual["message"], json!("source request failed: source responded 500 Internal Server Error"));
+    }
+
+    #[test]
+    fn failure_to_value_config() {
+        let error = ValidationError {
+            problems: vec![Problem {
+                path: String::from("source.hostname"),
+                reason: String::from("missing required field")
+            }]
+        };
+        let failure = Error::from(error).at(Stage::Event, None);
+
+        let expect = json!([{ "path": "source.hostname", "reason": "missing required field" }]);
+        let actual = failure.to_value();
+
+        assert_eq!(actual["kind"], json!("config"));
+        assert_eq!(actual["chunk"], Value::Null);
+        assert_eq!(actual["problems"], expect);
+    }
+}
@@ -0,0 +1,1816 @@
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{
+    DateTime,
+    Utc
+};
+use chrono_tz::Tz;
+use regex::Regex;
+use rusoto_core::Region;
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize
+};
+use serde_json::{
+    Map,
+    Value
+};
+
+use crate::checkpoint::Progress;
+use crate::error::Transport;
+use crate::mods::{
+    is_format,
+    to_step,
+    to_time
+};
+
+type Headers = BTreeMap<String, Vec<String>>;
+type QueryParams = Map<String, Value>;
+
+const METHODS: [&str; 7] = ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+const MIB: u64 = 1024 * 1024;
+
+const ENCRYPTIONS: [&str; 2] = ["AES256", "aws:kms"];
+
+const STORAGE_CLASSES: [&str; 8] = [
+    "STANDARD",
+    "REDUCED_REDUNDANCY",
+    "STANDARD_IA",
+    "ONEZONE_IA",
+    "INTELLIGENT_TIERING",
+    "GLACIER",
+    "DEEP_ARCHIVE",
+    "OUTPOSTS"
+];
+
+const ACLS: [&str; 7] = [
+    "private",
+    "public-read",
+    "public-read-write",
+    "authenticated-read",
+    "aws-exec-read",
+    "bucket-owner-read",
+    "bucket-owner-full-control"
+];
+
+/// Chunks fetched at once (bounds memory and connections of a Lambda)
+const MAX_CONCURRENCY: u64 = 32;
+
+/// Modifiers iterating over chunks (requests)
+const ITERATING: [&str; 5] = ["chunks", "cursor", "each", "link", "window"];
+
+/// Lambda Event
+///
+/// Typed form of the event payload consumed by pac-man (see README)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    #[serde(default)]
+    pub mods: Vec<ModConfig>,
+    /// Iteration of iterating modifiers (lockstep or nested loops)
+    #[serde(default = "Event::default_loop", rename = "loop")]
+    pub looping: Loop,
+    pub source: Source,
+    /// Persisted state read by `{:state:<key>}` and updated after a successful run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<State>,
+    /// Run progress persisted after every chunk to resume interrupted runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<Checkpoint>,
+    /// Chunks fetched (and stored) at once
+    #[serde(default = "Event::default_concurrency")]
+    pub concurrency: usize,
+    /// Milliseconds before the Lambda deadline after which no chunk is started (the run returns a continuation)
+    #[serde(default = "Event::default_deadline_margin")]
+    pub deadline_margin: u64,
+    /// Progress of the run continued by this event (set on continuation events)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<Progress>,
+    /// Single destination or array of destinations (body is written to each)
+    #[serde(deserialize_with = "one_or_many")]
+    pub destination: Vec<Destination>
+}
+
+impl Event {
+    fn default_loop() -> Loop { Loop::Lockstep }
+
+    fn default_concurrency() -> usize { 1 }
+
+    fn default_deadline_margin() -> u64 { 10_000 }
+}
+
+/// Iteration of iterating modifiers (see `mods::Modifiers`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Loop {
+    /// Every iterating modifier advances each chunk
+    Lockstep,
+    /// Iterating modifiers nest in configured order (first is the outer loop)
+    Nested
+}
+
+/// Deserialize single value or array of values
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>)
+    }
+
+    match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => Ok(vec![value]),
+        OneOrMany::Many(values) => Ok(values)
+    }
+}
+
+/// Event Source - request method, URI parts, headers and body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Source {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Headers>,
+    pub scheme: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    pub hostname: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<QueryParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fragment: Option<String>,
+    /// Raw string body or JSON body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<Retry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<Until>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<Rate>,
+    /// Milliseconds waited between pages (between batches of concurrent chunks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u64>
+}
+
+/// Source Rate Limit - token bucket of `requests` per `interval` milliseconds holding up to `burst` requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rate {
+    pub requests: u32,
+    #[serde(default = "Rate::default_interval")]
+    pub interval: u64,
+    /// Default requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>
+}
+
+impl Rate {
+    fn default_interval() -> u64 { 1000 }
+}
+
+/// Source Retry Policy - exponential backoff with optional jitter (delays in milliseconds)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Retry {
+    #[serde(default = "Retry::default_attempts")]
+    pub attempts: u32,
+    #[serde(default = "Retry::default_delay")]
+    pub delay: u64,
+    #[serde(default = "Retry::default_max_delay")]
+    pub max_delay: u64,
+    #[serde(default = "Retry::default_jitter")]
+    pub jitter: bool,
+    #[serde(default = "Retry::default_statuses")]
+    pub statuses: Vec<u16>,
+    #[serde(default = "Retry::default_errors")]
+    pub errors: Vec<Transport>
+}
+
+impl Retry {
+    fn default_attempts() -> u32 { 3 }
+
+    fn default_delay() -> u64 { 200 }
+
+    fn default_max_delay() -> u64 { 20_000 }
+
+    fn default_jitter() -> bool { true }
+
+    fn default_statuses() -> Vec<u16> { vec![429, 500, 502, 503, 504] }
+
+    fn default_errors() -> Vec<Transport> {
+        vec![Transport::Connect, Transport::Closed, Transport::Incomplete, Transport::Timeout]
+    }
+}
+
+/// Source Status Policy - accepted status codes and actions for others
+///
+/// Action keys are status codes (`404`) or classes (`4xx`); `errors` is the key template for `store`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accept: Option<Vec<u16>>,
+    #[serde(default)]
+    pub actions: BTreeMap<String, Action>,
+    #[serde(default = "Status::default_action")]
+    pub default: Action,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<String>
+}
+
+/// Action taken on a response status that isn't accepted
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// Fail run
+    Fail,
+    /// Stop chunk loop cleanly
+    Stop,
+    /// Skip chunk without storing body
+    Skip,
+    /// Store body to errors key and continue
+    Store
+}
+
+impl Status {
+    fn default_action() -> Action { Action::Fail }
+}
+
+/// Source Stop Conditions - evaluated against JSON response bodies (fields are JSON Pointers)
+///
+/// `items` locates the page items (an empty page stops), `size` is the page size (a short page stops),
+/// `more` locates a boolean (e.g. `has_more`) and `total` locates a total count of items across pages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Until {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub more: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<String>
+}
+
+/// Event Destination - S3 bucket or directory (collection) and object key or file path (name)
+///
+/// Objects are hashed (MD5 and SHA-256) while streaming unless `digest` is false. Bodies of unknown length
+/// or larger than `part_size` bytes are uploaded to S3 in parts (multipart upload).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Destination {
+    #[serde(default = "Destination::default_kind", rename = "type")]
+    pub kind: Kind,
+    /// AWS region (S3 only, signing region when endpoint is set)
+    #[serde(default)]
+    pub region: String,
+    /// S3-compatible endpoint (e.g. MinIO or LocalStack)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Path-style addressing (S3 only, virtual-hosted addressing is rejected by validation)
+    #[serde(default = "Destination::default_path_style")]
+    pub path_style: bool,
+    pub collection: String,
+    pub name: String,
+    /// S3 object options (S3 only)
+    #[serde(default)]
+    pub options: ObjectOptions,
+    #[serde(default = "Destination::default_mode")]
+    pub mode: Mode,
+    #[serde(default = "Destination::default_digest")]
+    pub digest: bool,
+    #[serde(default = "Destination::default_part_size")]
+    pub part_size: u64
+}
+
+/// S3 Object Options - server-side encryption, storage class, canned ACL, headers, tags and user metadata
+///
+/// Every value may contain placeholders (reduced per object)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObjectOptions {
+    /// `AES256` or `aws:kms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kms_key_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_class: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acl: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>
+}
+
+/// Write mode of destination objects
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Mode {
+    /// Always write objects
+    Overwrite,
+    /// Skip objects that already exist
+    IfAbsent,
+    /// Skip objects whose SHA-256 (stored in user metadata) is unchanged
+    IfChanged
+}
+
+/// Kind of destination
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    S3,
+    File
+}
+
+impl Destination {
+    fn default_kind() -> Kind { Kind::S3 }
+
+    fn default_path_style() -> bool { true }
+
+    fn default_mode() -> Mode { Mode::Overwrite }
+
+    fn default_digest() -> bool { true }
+
+    fn default_part_size() -> u64 { 8 * MIB }
+}
+
+/// Location of an object (or file) read by pac-man (e.g. list of `each` values)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    #[serde(default = "Destination::default_kind", rename = "type")]
+    pub kind: Kind,
+    #[serde(default)]
+    pub region: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    pub collection: String,
+    pub name: String
+}
+
+/// Job State - values (e.g. watermarks) persisted between runs in an S3 object (or file) named `<job>.json`
+///
+/// `update` values are reduced after every stored chunk; the values of the last stored chunk are persisted once
+/// the run completes (failed runs leave state unchanged)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct State {
+    #[serde(default = "Destination::default_kind", rename = "type")]
+    pub kind: Kind,
+    #[serde(default)]
+    pub region: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    pub collection: String,
+    pub job: String,
+    /// Values used until state is first persisted
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub initial: BTreeMap<String, String>,
+    pub update: BTreeMap<String, String>
+}
+
+impl State {
+    /// Object (or file) name of job state
+    pub fn name(&self) -> String {
+        format!("{}.json", self.job)
+    }
+
+    pub fn location(&self) -> Location {
+        Location {
+            kind: self.kind,
+            region: self.region.clone(),
+            endpoint: self.endpoint.clone(),
+            collection: self.collection.clone(),
+            name: self.name()
+        }
+    }
+}
+
+/// Checkpoint - progress of a run (modifier state, chunk index and objects written) persisted after every chunk
+///
+/// With `resume` a run continues from the persisted checkpoint; completed runs delete their checkpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    #[serde(flatten)]
+    pub location: Location,
+    #[serde(default)]
+    pub resume: bool
+}
+
+/// Modifier configuration (see `mods::load`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "lowercase")]
+pub enum ModConfig {
+    Chunks {
+        start: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        end: Option<u64>,
+        chunk: ChunkConfig,
+        #[serde(default)]
+        bytes: i64
+    },
+    Cursor {
+        pointer: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start: Option<String>
+    },
+    Each {
+        /// Inline values (or `from`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        values: Option<Vec<Value>>,
+        /// JSON object (or file) holding values
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<Location>,
+        /// JSON Pointer to values array within `from` (default root)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pointer: Option<String>
+    },
+    Datetime {
+        /// IANA timezone (e.g. Europe/Berlin, default UTC)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timezone: Option<String>
+    },
+    Link,
+    Secrets {
+        region: String
+    },
+    Uuid,
+    Window {
+        start: String,
+        /// Default now
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        end: Option<String>,
+        step: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timezone: Option<String>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkConfig {
+    pub length: u64
+}
+
+/// Problem found in event at field path (e.g. `source.params.from`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub path: String,
+    pub reason: String
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() { return write!(f, "{}", self.reason); }
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+/// Every problem found while validating an event
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub problems: Vec<Problem>
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let problems: Vec<String> = self.problems
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        write!(f, "invalid event: {}", problems.join("; "))
+    }
+}
+
+impl error::Error for ValidationError {}
+
+impl Event {
+    /// Validate and convert raw Lambda event into Event
+    ///
+    /// Validation is completed before conversion so all problems are reported at once
+    pub fn from_value(value: Value) -> Result<Self, ValidationError> {
+        let mut validator = Validator::new();
+        validator.event(&value);
+        if !validator.problems.is_empty() {
+            return Err(ValidationError { problems: validator.problems });
+        }
+
+        serde_json::from_value(value).map_err(|e| ValidationError {
+            problems: vec![Problem { path: String::new(), reason: e.to_string() }]
+        })
+    }
+}
+
+/// Join field path and key
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() { return String::from(key); }
+    format!("{}.{}", path, key)
+}
+
+/// Validator walks a raw event collecting problems with field paths
+struct Validator {
+    problems: Vec<Problem>
+}
+
+impl Validator {
+    fn new() -> Self {
+        Validator { problems: vec![] }
+    }
+
+    fn problem(&mut self, path: &str, reason: &str) {
+        self.problems.push(Problem {
+            path: String::from(path),
+            reason: String::from(reason)
+        });
+    }
+
+    fn object<'a>(&mut self, value: &'a Value, path: &str) -> Option<&'a Map<String, Value>> {
+        let object = value.as_object();
+        if object.is_none() { self.problem(path, "expected object"); }
+        object
+    }
+
+    fn field<'a>(
+        &mut self,
+        parent: &'a Map<String, Value>,
+        path: &str,
+        key: &str,
+        required: bool
+    ) -> Option<&'a Value> {
+        match parent.get(key) {
+            Some(Value::Null) | None => {
+                if required { self.problem(&join(path, key), "missing required field"); }
+                None
+            }
+            value => value
+        }
+    }
+
+    fn string<'a>(
+        &mut self,
+        parent: &'a Map<String, Value>,
+        path: &str,
+        key: &str,
+        required: bool
+    ) -> Option<&'a str> {
+        let value = self.field(parent, path, key, required)?;
+        let string = value.as_str();
+        if string.is_none() { self.problem(&join(path, key), "expected string"); }
+        string
+    }
+
+    fn non_empty_string<'a>(
+        &mut self,
+        parent: &'a Map<String, Value>,
+        path: &str,
+        key: &str,
+        required: bool
+    ) -> Option<&'a str> {
+        let string = self.string(parent, path, key, required)?;
+        if string.is_empty() {
+            self.problem(&join(path, key), "must not be empty");
+            return None;
+        }
+        Some(string)
+    }
+
+    fn unsigned(
+        &mut self,
+        parent: &Map<String, Value>,
+        path: &str,
+        key: &str,
+        required: bool
+    ) -> Option<u64> {
+        let value = self.field(parent, path, key, required)?;
+        let unsigned = value.as_u64();
+        if unsigned.is_none() { self.problem(&join(path, key), "expected unsigned integer"); }
+        unsigned
+    }
+
+    fn integer(
+        &mut self,
+        parent: &Map<String, Value>,
+        path: &str,
+        key: &str,
+        required: bool
+    ) -> Option<i64> {
+        let value = self.field(parent, path, key, required)?;
+        let integer = value.as_i64();
+        if integer.is_none() { self.problem(&join(path, key), "expected integer"); }
+        integer
+    }
+
+    fn boolean(
+        &mut self,
+        parent: &Map<String, Value>,
+        path: &str,
+        key: &str,
+        required: bool
+    ) -> Option<bool> {
+        let value = self.field(parent, path, key, required)?;
+        let boolean = value.as_bool();
+        if boolean.is_none() { self.problem(&join(path, key), "expected boolean"); }
+        boolean
+    }
+
+    fn array<'a>(
+        &mut self,
+        parent: &'a Map<String, Value>,
+        path: &str,
+        key: &str,
+        required: bool
+    ) -> Option<&'a Vec<Value>> {
+        let value = self.field(parent, path, key, required)?;
+        let array = value.as_array();
+        if array.is_none() { self.problem(&join(path, key), "expected array"); }
+        array
+    }
+
+    fn pointer<'a>(
+        &mut self,
+        parent: &'a Map<String, Value>,
+        path: &str,
+        key: &str,
+        required: bool
+    ) -> Option<&'a str> {
+        let pointer = self.string(parent, path, key, required)?;
+        if !pointer.is_empty() && !pointer.starts_with('/') {
+            self.problem(&join(path, key), "expected JSON Pointer (e.g. /data)");
+            return None;
+        }
+        Some(pointer)
+    }
+
+    fn timezone(&mut self, parent: &Map<String, Value>, path: &str) {
+        if let Some(timezone) = self.string(parent, path, "timezone", false) {
+            if Tz::from_str(timezone).is_err() {
+                self.problem(&join(path, "timezone"), &format!("unknown timezone \"{}\"", timezone));
+            }
+        }
+    }
+
+    fn time(&mut self, value: &str, path: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let time = to_time(value, now);
+        if time.is_none() { self.problem(path, "expected RFC 3339 time, date or expression (e.g. now-1d)"); }
+        time
+    }
+
+    fn region(&mut self, parent: &Map<String, Value>, path: &str, key: &str) {
+        if let Some(region) = self.string(parent, path, key, true) {
+            if Region::from_str(region).is_err() {
+                self.problem(&join(path, key), &format!("unknown AWS region \"{}\"", region));
+            }
+        }
+    }
+
+    fn event(&mut self, value: &Value) {
+        let event = match self.object(value, "") {
+            Some(event) => event,
+            _ => return
+        };
+
+        if let Some(mods) = self.field(event, "", "mods", false) {
+            match mods.as_array() {
+                Some(mods) => {
+                    for (i, config) in mods.iter().enumerate() {
+                        self.modifier(config, &format!("mods[{}]", i));
+                    }
+                }
+                _ => self.problem("mods", "expected array")
+            }
+        }
+
+        if let Some(source) = self.field(event, "", "source", true) {
+            self.source(source, "source");
+        }
+        if let Some(state) = self.field(event, "", "state", false) {
+            self.state(state, "state");
+        }
+        if let Some(checkpoint) = self.field(event, "", "checkpoint", false) {
+            self.location(checkpoint, "checkpoint");
+            if let Some(checkpoint) = checkpoint.as_object() {
+                self.boolean(checkpoint, "checkpoint", "resume", false);
+            }
+        }
+        if let Some(destination) = self.field(event, "", "destination", true) {
+            match destination.as_array() {
+                Some(destinations) if destinations.is_empty() => self.problem("destination", "must not be empty"),
+                Some(destinations) => {
+                    for (i, destination) in destinations.iter().enumerate() {
+                        self.destination(destination, &format!("destination[{}]", i));
+                    }
+                }
+                _ => self.destination(destination, "destination")
+            }
+        }
+
+        if let Some(concurrency) = self.unsigned(event, "", "concurrency", false) {
+            if !(1..=MAX_CONCURRENCY).contains(&concurrency) {
+                self.problem("concurrency", &format!("must be between 1 and {}", MAX_CONCURRENCY));
+            } else if concurrency > 1 {
+                self.concurrent(event);
+            }
+        }
+        self.unsigned(event, "", "deadline_margin", false);
+        if let Some(continuation) = self.field(event, "", "continuation", false) {
+            self.object(continuation, "continuation");
+        }
+
+        if let Some(looping) = self.field(event, "", "loop", false) {
+            match serde_json::from_value::<Loop>(looping.clone()) {
+                Ok(Loop::Nested) => self.nested(event),
+                Ok(Loop::Lockstep) => {}
+                _ => self.problem("loop", "expected one of lockstep, nested")
+            }
+        }
+    }
+
+    /// Concurrent chunks are prepared before responses are observed so pages must not depend on responses
+    fn concurrent(&mut self, event: &Map<String, Value>) {
+        if let Some("nested") = event.get("loop").and_then(Value::as_str) {
+            self.problem("concurrency", "not supported with nested loops");
+        }
+        let mods = event.get("mods").and_then(Value::as_array).cloned().unwrap_or_default();
+        for (i, config) in mods.iter().enumerate() {
+            if let Some(name) = config.get("name").and_then(Value::as_str).filter(|n| ["cursor", "link"].contains(n)) {
+                self.problem(&format!("mods[{}].name", i), &format!("{} pages depend on responses (concurrency must be 1)", name));
+            }
+        }
+        // pages past the last are fetched speculatively unless the chunks range ends
+        let bounded = mods.iter().any(|config| {
+            config.get("name").and_then(Value::as_str) == Some("chunks") && matches!(config.get("end"), Some(end) if !end.is_null())
+        });
+        if !bounded {
+            self.problem("concurrency", "requires a chunks modifier with an end");
+        }
+    }
+
+    /// Nested loops require distinct iterating modifiers each named by destination names (names are unique per
+    /// combination of loops)
+    fn nested(&mut self, event: &Map<String, Value>) {
+        let mods = event.get("mods").and_then(Value::as_array).cloned().unwrap_or_default();
+        let mut loops = vec![];
+        for (i, config) in mods.iter().enumerate() {
+            match config.get("name").and_then(Value::as_str) {
+                Some(name) if ITERATING.contains(&name) => {
+                    if loops.contains(&name) {
+                        self.problem(&format!("mods[{}].name", i), "nested loops require distinct modifiers");
+                    }
+                    loops.push(name);
+                }
+                _ => {}
+            }
+        }
+
+        let destinations = match event.get("destination") {
+            Some(Value::Array(destinations)) => destinations
+                .iter()
+                .enumerate()
+                .map(|(i, d)| (format!("destination[{}].name", i), d.get("name")))
+                .collect(),
+            Some(destination) => vec![(String::from("destination.name"), destination.get("name"))],
+            _ => vec![]
+        };
+        for (path, name) in destinations {
+            if let Some(name) = name.and_then(Value::as_str) {
+                for m in loops.iter() {
+                    if !name.contains(&format!("{{:{}:", m)) {
+                        self.problem(&path, &format!("must contain a {{:{}:...}} placeholder with nested loops", m));
+                    }
+                }
+            }
+        }
+    }
+
+    fn source(&mut self, value: &Value, path: &str) {
+        let source = match self.object(value, path) {
+            Some(source) => source,
+            _ => return
+        };
+
+        if let Some(method) = self.string(source, path, "method", false) {
+            if !METHODS.contains(&method.to_uppercase().as_str()) {
+                self.problem(&join(path, "method"), &format!("expected one of {}", METHODS.join(", ")));
+            }
+        }
+
+        if let Some(headers) = self.field(source, path, "headers", false) {
+            let path = join(path, "headers");
+            if let Some(headers) = self.object(headers, &path) {
+                for (header, values) in headers.iter() {
+                    let path = join(&path, header);
+                    match values.as_array() {
+                        Some(values) => {
+                            for (i, value) in values.iter().enumerate() {
+                                if !value.is_string() {
+                                    self.problem(&format!("{}[{}]", path, i), "expected string");
+                                }
+                            }
+                        }
+                        _ => self.problem(&path, "expected array of strings")
+                    }
+                }
+            }
+        }
+
+        if let Some(scheme) = self.string(source, path, "scheme", true) {
+            if scheme != "http" && scheme != "https" {
+                self.problem(&join(path, "scheme"), "expected \"http\" or \"https\"");
+            }
+        }
+
+        let username = self.string(source, path, "username", false);
+        let password = self.string(source, path, "password", false);
+        match (username, password) {
+            (Some(_), None) => self.problem(&join(path, "password"), "required with username"),
+            (None, Some(_)) => self.problem(&join(path, "username"), "required with password"),
+            _ => {}
+        }
+
+        self.non_empty_string(source, path, "hostname", true);
+
+        if let Some(port) = self.unsigned(source, path, "port", false) {
+            if port == 0 || port > 65535 {
+                self.problem(&join(path, "port"), "must be between 1 and 65535");
+            }
+        }
+
+        self.string(source, path, "path", false);
+
+        if let Some(params) = self.field(source, path, "params", false) {
+            let path = join(path, "params");
+            if let Some(params) = self.object(params, &path) {
+                for (name, value) in params.iter() {
+                    if !value.is_string() { self.problem(&join(&path, name), "expected string"); }
+                }
+            }
+        }
+
+        self.string(source, path, "fragment", false);
+
+        if let Some(retry) = self.field(source, path, "retry", false) {
+            self.retry(retry, &join(path, "retry"));
+        }
+
+        if let Some(status) = self.field(source, path, "status", false) {
+            self.status(status, &join(path, "status"));
+        }
+
+        if let Some(until) = self.field(source, path, "until", false) {
+            self.until(until, &join(path, "until"));
+        }
+
+        if let Some(rate) = self.field(source, path, "rate", false) {
+            self.rate(rate, &join(path, "rate"));
+        }
+        self.unsigned(source, path, "delay", false);
+    }
+
+    fn rate(&mut self, value: &Value, path: &str) {
+        let rate = match self.object(value, path) {
+            Some(rate) => rate,
+            _ => return
+        };
+
+        for key in ["requests", "burst"].iter() {
+            if let Some(count) = self.unsigned(rate, path, key, *key == "requests") {
+                if count == 0 || count > u64::from(u32::MAX) {
+                    self.problem(&join(path, key), "must be between 1 and 4294967295");
+                }
+            }
+        }
+        if let Some(0) = self.unsigned(rate, path, "interval", false) {
+            self.problem(&join(path, "interval"), "must be greater than 0");
+        }
+    }
+
+    fn retry(&mut self, value: &Value, path: &str) {
+        let retry = match self.object(value, path) {
+            Some(retry) => retry,
+            _ => return
+        };
+
+        if let Some(attempts) = self.unsigned(retry, path, "attempts", false) {
+            if attempts == 0 || attempts > u64::from(u32::MAX) {
+                self.problem(&join(path, "attempts"), "must be between 1 and 4294967295");
+            }
+        }
+        self.unsigned(retry, path, "delay", false);
+        self.unsigned(retry, path, "max_delay", false);
+        self.boolean(retry, path, "jitter", false);
+
+        if let Some(statuses) = self.array(retry, path, "statuses", false) {
+            for (i, status) in statuses.iter().enumerate() {
+                match status.as_u64() {
+                    Some(status) if (100..600).contains(&status) => {}
+                    _ => self.problem(&format!("{}.statuses[{}]", path, i), "expected HTTP status code")
+                }
+            }
+        }
+
+        if let Some(errors) = self.array(retry, path, "errors", false) {
+            for (i, error) in errors.iter().enumerate() {
+                if serde_json::from_value::<Transport>(error.clone()).is_err() {
+                    self.problem(
+                        &format!("{}.errors[{}]", path, i),
+                        "expected one of connect, closed, incomplete, timeout"
+                    );
+                }
+            }
+        }
+    }
+
+    fn action(&mut self, value: &Value, path: &str) -> Option<Action> {
+        let action = serde_json::from_value::<Action>(value.clone()).ok();
+        if action.is_none() { self.problem(path, "expected one of fail, stop, skip, store"); }
+        action
+    }
+
+    fn status(&mut self, value: &Value, path: &str) {
+        let status = match self.object(value, path) {
+            Some(status) => status,
+            _ => return
+        };
+
+        if let Some(accept) = self.array(status, path, "accept", false) {
+            for (i, code) in accept.iter().enumerate() {
+                match code.as_u64() {
+                    Some(code) if (100..600).contains(&code) => {}
+                    _ => self.problem(&format!("{}.accept[{}]", path, i), "expected HTTP status code")
+                }
+            }
+        }
+
+        let mut actions = vec![];
+        if let Some(configured) = self.field(status, path, "actions", false) {
+            let path = join(path, "actions");
+            if let Some(configured) = self.object(configured, &path) {
+                let key = Regex::new(r"^[1-5]([0-9]{2}|xx)$").unwrap();
+                for (code, action) in configured.iter() {
+                    let path = join(&path, code);
+                    if !key.is_match(code) { self.problem(&path, "expected status code or class (e.g. 404 or 4xx)"); }
+                    actions.push(self.action(action, &path));
+                }
+            }
+        }
+        if let Some(default) = self.field(status, path, "default", false) {
+            actions.push(self.action(default, &join(path, "default")));
+        }
+
+        let errors = self.non_empty_string(status, path, "errors", false);
+        if errors.is_none() && actions.contains(&Some(Action::Store)) {
+            self.problem(&join(path, "errors"), "required with store action");
+        }
+    }
+
+    fn until(&mut self, value: &Value, path: &str) {
+        let until = match self.object(value, path) {
+            Some(until) => until,
+            _ => return
+        };
+
+        // size and total count items (each invalid field is reported once)
+        let items = self.pointer(until, path, "items", false).is_some();
+        let requires_items = |key: &str| !items && matches!(until.get(key), Some(v) if !v.is_null());
+        if requires_items("size") {
+            self.problem(&join(path, "size"), "requires items");
+        } else if let Some(0) = self.unsigned(until, path, "size", false) {
+            self.problem(&join(path, "size"), "must be greater than 0");
+        }
+        self.pointer(until, path, "more", false);
+        if requires_items("total") {
+            self.problem(&join(path, "total"), "requires items");
+        } else {
+            self.pointer(until, path, "total", false);
+        }
+    }
+
+    /// Destination (or location) type, S3 when absent
+    fn kind(&mut self, parent: &Map<String, Value>, path: &str) -> Option<Kind> {
+        match self.field(parent, path, "type", false) {
+            Some(kind) => match serde_json::from_value::<Kind>(kind.clone()) {
+                Ok(kind) => Some(kind),
+                _ => {
+                    self.problem(&join(path, "type"), "expected one of s3, file");
+                    None
+                }
+            },
+            _ => Some(Kind::S3)
+        }
+    }
+
+    /// S3 endpoint URL (region is optional) or AWS region
+    fn endpoint(&mut self, parent: &Map<String, Value>, path: &str) {
+        match self.string(parent, path, "endpoint", false) {
+            Some(endpoint) => {
+                if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                    self.problem(&join(path, "endpoint"), "expected http or https URL");
+                }
+                self.string(parent, path, "region", false);
+            }
+            _ => self.region(parent, path, "region")
+        }
+    }
+
+    fn location(&mut self, value: &Value, path: &str) {
+        let location = match self.object(value, path) {
+            Some(location) => location,
+            _ => return
+        };
+
+        if let Some(Kind::S3) = self.kind(location, path) { self.endpoint(location, path); }
+        self.non_empty_string(location, path, "collection", true);
+        self.non_empty_string(location, path, "name", true);
+    }
+
+    fn state(&mut self, value: &Value, path: &str) {
+        let state = match self.object(value, path) {
+            Some(state) => state,
+            _ => return
+        };
+
+        if let Some(Kind::S3) = self.kind(state, path) { self.endpoint(state, path); }
+        self.non_empty_string(state, path, "collection", true);
+        if let Some(job) = self.non_empty_string(state, path, "job", true) {
+            if job.contains('/') || job.starts_with('.') {
+                self.problem(&join(path, "job"), "must not contain / or start with .");
+            }
+        }
+        for key in ["initial", "update"].iter() {
+            if let Some(values) = self.field(state, path, key, *key == "update") {
+                let path = join(path, key);
+                if let Some(values) = self.object(values, &path) {
+                    for (name, value) in values.iter() {
+                        if !value.is_string() { self.problem(&join(&path, name), "expected string"); }
+                    }
+                    if *key == "update" && values.is_empty() { self.problem(&path, "must not be empty"); }
+                }
+            }
+        }
+    }
+
+    fn destination(&mut self, value: &Value, path: &str) {
+        let destination = match self.object(value, path) {
+            Some(destination) => destination,
+            _ => return
+        };
+
+        let kind = self.kind(destination, path);
+        if let Some(Kind::S3) = kind {
+            self.endpoint(destination, path);
+            if let Some(false) = self.boolean(destination, path, "path_style", false) {
+                self.problem(&join(path, "path_style"), "only path-style addressing is supported");
+            }
+            if let Some(options) = self.field(destination, path, "options", false) {
+                self.options(options, &join(path, "options"));
+            }
+        } else if destination.contains_key("options") {
+            self.problem(&join(path, "options"), "only supported by s3 destinations");
+        }
+        if let Some(mode) = self.field(destination, path, "mode", false) {
+            match serde_json::from_value::<Mode>(mode.clone()) {
+                Ok(Mode::IfChanged) if kind == Some(Kind::File) => {
+                    self.problem(&join(path, "mode"), "if-changed is only supported by s3 destinations");
+                }
+                Ok(_) => {}
+                _ => self.problem(&join(path, "mode"), "expected one of overwrite, if-absent, if-changed")
+            }
+        }
+        self.non_empty_string(destination, path, "collection", true);
+        self.non_empty_string(destination, path, "name", true);
+        self.boolean(destination, path, "digest", false);
+        if let Some(part_size) = self.unsigned(destination, path, "part_size", false) {
+            // S3 multipart upload part limits
+            if !(5 * MIB..=5 * 1024 * MIB).contains(&part_size) {
+                self.problem(&join(path, "part_size"), "must be between 5242880 (5 MiB) and 5368709120 (5 GiB)");
+            }
+        }
+    }
+
+    /// String that is one of values (values containing placeholders are checked once reduced by S3)
+    fn one_of(&mut self, parent: &Map<String, Value>, path: &str, key: &str, values: &[&str]) -> Option<String> {
+        let value = self.string(parent, path, key, false)?;
+        if !value.contains("{:") && !values.contains(&value) {
+            self.problem(&join(path, key), &format!("expected one of {}", values.join(", ")));
+        }
+        Some(String::from(value))
+    }
+
+    fn options(&mut self, value: &Value, path: &str) {
+        let options = match self.object(value, path) {
+            Some(options) => options,
+            _ => return
+        };
+
+        let encryption = self.one_of(options, path, "encryption", &ENCRYPTIONS);
+        if self.non_empty_string(options, path, "kms_key_id", false).is_some() && encryption.as_deref() != Some("aws:kms") {
+            self.problem(&join(path, "kms_key_id"), "requires encryption aws:kms");
+        }
+        self.one_of(options, path, "storage_class", &STORAGE_CLASSES);
+        self.one_of(options, path, "acl", &ACLS);
+        self.string(options, path, "cache_control", false);
+        self.string(options, path, "content_encoding", false);
+
+        for key in ["tags", "metadata"].iter() {
+            if let Some(pairs) = self.field(options, path, key, false) {
+                let path = join(path, key);
+                if let Some(pairs) = self.object(pairs, &path) {
+                    for (name, value) in pairs.iter() {
+                        if !value.is_string() { self.problem(&join(&path, name), "expected string"); }
+                    }
+                    // S3 object tag limit
+                    if *key == "tags" && pairs.len() > 10 { self.problem(&path, "must not exceed 10 tags"); }
+                }
+            }
+        }
+    }
+
+    fn modifier(&mut self, value: &Value, path: &str) {
+        let config = match self.object(value, path) {
+            Some(config) => config,
+            _ => return
+        };
+
+        match self.string(config, path, "name", true) {
+            Some("chunks") => {
+                let start = self.unsigned(config, path, "start", true);
+                let end = self.unsigned(config, path, "end", false);
+                if let (Some(start), Some(end)) = (start, end) {
+                    if end < start { self.problem(&join(path, "end"), "must not be less than start"); }
+                }
+                if let Some(chunk) = self.field(config, path, "chunk", true) {
+                    let path = join(path, "chunk");
+                    if let Some(chunk) = self.object(chunk, &path) {
+                        if let Some(0) = self.unsigned(chunk, &path, "length", true) {
+                            self.problem(&join(&path, "length"), "must be greater than 0");
+                        }
+                    }
+                }
+                self.integer(config, path, "bytes", false);
+            }
+            Some("cursor") => {
+                self.pointer(config, path, "pointer", true);
+                self.string(config, path, "start", false);
+            }
+            Some("datetime") => self.timezone(config, path),
+            Some("each") => {
+                let values = self.array(config, path, "values", false);
+                let from = self.field(config, path, "from", false);
+                match (values, from) {
+                    (Some(values), None) if values.is_empty() => {
+                        self.problem(&join(path, "values"), "must not be empty");
+                    }
+                    (Some(_), Some(_)) => self.problem(&join(path, "from"), "not allowed with values"),
+                    (None, Some(from)) => self.location(from, &join(path, "from")),
+                    (None, None) => self.problem(path, "requires values or from"),
+                    _ => {}
+                }
+                if self.pointer(config, path, "pointer", false).is_some() && values.is_some() {
+                    self.problem(&join(path, "pointer"), "requires from");
+                }
+            }
+            Some("link") => {}
+            Some("secrets") => self.region(config, path, "region"),
+            Some("uuid") => {}
+            Some("window") => {
+                let now = Utc::now();
+                let start = self.string(config, path, "start", true);
+                let end = self.string(config, path, "end", false);
+                let start = start.and_then(|start| self.time(start, &join(path, "start"), now));
+                let end = end.and_then(|end| self.time(end, &join(path, "end"), now));
+                if let (Some(start), Some(end)) = (start, end) {
+                    if end < start { self.problem(&join(path, "end"), "must not be before start"); }
+                }
+                if let Some(step) = self.string(config, path, "step", true) {
+                    if to_step(step).is_none() {
+                        self.problem(&join(path, "step"), "expected step (e.g. 1h, 1d, 1 month)");
+                    }
+                }
+                if let Some(format) = self.string(config, path, "format", false) {
+                    if !is_format(format) { self.problem(&join(path, "format"), "invalid strftime format"); }
+                }
+                self.timezone(config, path);
+            }
+            Some(name) => self.problem(&join(path, "name"), &format!("unknown modifier \"{}\"", name)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn minima() -> Value {
+        json!({
+            "source": {
+                "scheme": "https",
+                "hostname": "example.com"
+            },
+            "destination": {
+                "region": "us-east-1",
+                "collection": "bucket-name",
+                "name": "key"
+            }
+        })
+    }
+
+    fn maxima() -> Value {
+        json!({
+            "mods": [
+                {
+                    "name": "chunks",
+                    "start": 0,
+                    "end": 100,
+                    "chunk": {
+                        "length": 10
+                    },
+                    "bytes": 100
+                },
+                {
+                    "name": "secrets",
+                    "region": "us-east-1"
+                },
+                {
+                    "name": "uuid"
+                }
+            ],
+            "source": {
+                "headers": {
+                    "Accepts": [
+                        "text/json"
+                    ]
+                },
+                "scheme": "https",
+                "username": "pseudo",
+                "password": "{:secrets:pac-man:pw}",
+                "hostname": "example.com",
+                "port": 8080,
+                "path": "/follow/the",
+                "params": {
+                    "from": "{:chunks:chunk:start}",
+                    "to": "{:chunks:chunk:end}"
+                },
+                "fragment": "/yellow/brick/road"
+            },
+            "destination": {
+                "region": "us-east-1",
+                "collection": "bucket-name",
+                "name": "key-{:uuid}"
+            }
+        })
+    }
+
+    #[test]
+    fn event_from_value_minima() {
+        let event = Event::from_value(minima()).unwrap();
+
+        assert!(event.mods.is_empty());
+        assert_eq!(event.source.hostname, "example.com");
+        assert_eq!(event.destination[0].collection, "bucket-name");
+    }
+
+    #[test]
+    fn event_from_value_maxima() {
+        let event = Event::from_value(maxima()).unwrap();
+
+        let expect_mods = vec![
+            ModConfig::Chunks {
+                start: 0,
+                end: Some(100),
+                chunk: ChunkConfig { length: 10 },
+                bytes: 100
+            },
+            ModConfig::Secrets { region: String::from("us-east-1") },
+            ModConfig::Uuid
+        ];
+
+        assert_eq!(event.mods, expect_mods);
+        assert_eq!(event.source.port, Some(8080));
+        assert_eq!(event.source.headers.unwrap()["Accepts"], vec![String::from("text/json")]);
+    }
+
+    #[test]
+    fn event_from_value_not_object() {
+        let expect = vec![String::new()];
+        let actual = paths(Event::from_value(json!([])).unwrap_err());
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_missing_sections() {
+        let expect = vec![String::from("source"), String::from("destination")];
+        let actual = paths(Event::from_value(json!({})).unwrap_err());
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_reports_all_problems() {
+        let mut event = maxima();
+        event["source"]["params"]["from"] = json!(0);
+        event["source"]["port"] = json!(70000);
+        event["source"]["headers"]["Accepts"] = json!("text/json");
+        event["destination"]["region"] = json!("mars-north-1");
+        event["destination"].as_object_mut().unwrap().remove("name");
+        event["mods"][0]["chunk"]["length"] = json!(0);
+        event["mods"][2]["name"] = json!("unknown");
+
+        let expect = vec![
+            String::from("mods[0].chunk.length"),
+            String::from("mods[2].name"),
+            String::from("source.headers.Accepts"),
+            String::from("source.port"),
+            String::from("source.params.from"),
+            String::from("destination.region"),
+            String::from("destination.name")
+        ];
+        let actual = paths(Event::from_value(event).unwrap_err());
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_credentials_pair() {
+        let mut event = minima();
+        event["source"]["username"] = json!("pseudo");
+
+        let error = Event::from_value(event).unwrap_err();
+
+        let expect = vec![Problem {
+            path: String::from("source.password"),
+            reason: String::from("required with username")
+        }];
+
+        assert_eq!(error.problems, expect);
+    }
+
+    #[test]
+    fn validation_error_display() {
+        let error = ValidationError {
+            problems: vec![
+                Problem { path: String::from("source.params.from"), reason: String::from("expected string") },
+                Problem { path: String::from("destination"), reason: String::from("missing required field") }
+            ]
+        };
+
+        let expect = "invalid event: source.params.from: expected string; destination: missing required field";
+        let actual = error.to_string();
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_method_and_body() {
+        let mut event = minima();
+        event["source"]["method"] = json!("post");
+        event["source"]["body"] = json!({ "query": "{ items(first: 10) { id } }" });
+
+        let event = Event::from_value(event).unwrap();
+
+        assert_eq!(event.source.method, Some(String::from("post")));
+        assert_eq!(event.source.body, Some(json!({ "query": "{ items(first: 10) { id } }" })));
+    }
+
+    #[test]
+    fn event_from_value_unknown_method() {
+        let mut event = minima();
+        event["source"]["method"] = json!("FETCH");
+
+        let expect = vec![String::from("source.method")];
+        let actual = paths(Event::from_value(event).unwrap_err());
+
+        assert_eq!(actual, expect);
+    }
+
+    fn paths(error: ValidationError) -> Vec<String> {
+        error.problems.into_iter().map(|p| p.path).collect()
+    }
+
+    #[test]
+    fn event_from_value_retry_defaults() {
+        let mut event = minima();
+        event["source"]["retry"] = json!({ "attempts": 5 });
+
+        let event = Event::from_value(event).unwrap();
+        let retry = event.source.retry.unwrap();
+
+        assert_eq!(retry.attempts, 5);
+        assert_eq!(retry.delay, 200);
+        assert_eq!(retry.statuses, vec![429, 500, 502, 503, 504]);
+        assert_eq!(retry.errors.len(), 4);
+    }
+
+    #[test]
+    fn event_from_value_invalid_retry() {
+        let mut event = minima();
+        event["source"]["retry"] = json!({
+            "attempts": 0,
+            "jitter": "yes",
+            "statuses": [503, 99],
+            "errors": ["connect", "reset"]
+        });
+
+        let expect = vec![
+            String::from("source.retry.attempts"),
+            String::from("source.retry.jitter"),
+            String::from("source.retry.statuses[1]"),
+            String::from("source.retry.errors[1]")
+        ];
+        let actual = paths(Event::from_value(event).unwrap_err());
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_status() {
+        let mut event = minima();
+        event["source"]["status"] = json!({
+            "accept": [200],
+            "actions": { "404": "stop", "5xx": "store" },
+            "errors": "errors/key"
+        });
+
+        let status = Event::from_value(event).unwrap().source.status.unwrap();
+
+        assert_eq!(status.accept, Some(vec![200]));
+        assert_eq!(status.actions["404"], Action::Stop);
+        assert_eq!(status.actions["5xx"], Action::Store);
+        assert_eq!(status.default, Action::Fail);
+    }
+
+    #[test]
+    fn event_from_value_invalid_status() {
+        let mut event = minima();
+        event["source"]["status"] = json!({
+            "accept": [200, 1000],
+            "actions": { "404": "ignore", "4x": "skip" },
+            "default": "store"
+        });
+
+        let expect = vec![
+            String::from("source.status.accept[1]"),
+            String::from("source.status.actions.404"),
+            String::from("source.status.actions.4x"),
+            String::from("source.status.errors")
+        ];
+        let actual = paths(Event::from_value(event).unwrap_err());
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_cursor() {
+        let mut event = minima();
+        event["mods"] = json!([{ "name": "cursor", "pointer": "/links/next" }]);
+
+        let expect = vec![ModConfig::Cursor { pointer: String::from("/links/next"), start: None }];
+        let actual = Event::from_value(event).unwrap().mods;
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_invalid_cursor() {
+        let mut event = minima();
+        event["mods"] = json!([{ "name": "cursor", "pointer": "next_cursor", "start": 1 }]);
+
+        let expect = vec![String::from("mods[0].pointer"), String::from("mods[0].start")];
+        let actual = paths(Event::from_value(event).unwrap_err());
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_until() {
+        let mut event = minima();
+        event["source"]["until"] = json!({ "items": "/data", "size": 100, "more": "/has_more" });
+
+        let expect = Some(Until {
+            items: Some(String::from("/data")),
+            size: Some(100),
+            more: Some(String::from("/has_more")),
+            total: None
+        });
+        let actual = Event::from_value(event).unwrap().source.until;
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_invalid_until() {
+        let mut event = minima();
+        event["source"]["until"] = json!({ "size": 0, "more": "has_more", "total": "/total" });
+
+        let expect = vec![
+            (String::from("source.until.size"), String::from("requires items")),
+            (String::from("source.until.more"), String::from("expected JSON Pointer (e.g. /data)")),
+            (String::from("source.until.total"), String::from("requires items"))
+        ];
+        let actual: Vec<(String, String)> = Event::from_value(event.clone())
+            .unwrap_err()
+            .problems
+            .into_iter()
+            .map(|p| (p.path, p.reason))
+            .collect();
+
+        assert_eq!(actual, expect);
+
+        event["source"]["until"] = json!({ "items": "/data", "size": 0, "total": "total" });
+
+        let expect = vec![
+            (String::from("source.until.size"), String::from("must be greater than 0")),
+            (String::from("source.until.total"), String::from("expected JSON Pointer (e.g. /data)"))
+        ];
+        let actual: Vec<(String, String)> = Event::from_value(event)
+            .unwrap_err()
+            .problems
+            .into_iter()
+            .map(|p| (p.path, p.reason))
+            .collect();
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_file_destination() {
+        let mut event = minima();
+        event["destination"] = json!({ "type": "file", "collection": "/mnt/efs", "name": "data/key" });
+
+        let actual = Event::from_value(event).unwrap().destination.remove(0);
+
+        assert_eq!(actual.kind, Kind::File);
+        assert_eq!(actual.region, String::new());
+    }
+
+    #[test]
+    fn event_from_value_invalid_destination_type() {
+        let mut event = minima();
+        event["destination"]["type"] = json!("ftp");
+        event["destination"]["region"] = json!("nowhere");
+
+        let expect = vec![String::from("destination.type")];
+        let actual = paths(Event::from_value(event).unwrap_err());
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_destinations() {
+        let mut event = minima();
+        let destination = event["destination"].clone();
+        event["destination"] = json!([destination, { "type": "file", "collection": "/tmp", "name": "key" }]);
+
+        let actual = Event::from_value(event).unwrap().destination;
+
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[1].kind, Kind::File);
+    }
+
+    #[test]
+    fn event_from_value_invalid_destinations() {
+        let mut event = minima();
+        event["destination"] = json!([{ "region": "us-east-1", "collection": "bucket-name" }]);
+        assert_eq!(paths(Event::from_value(event.clone()).unwrap_err()), vec![String::from("destination[0].name")]);
+
+        event["destination"] = json!([]);
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), vec![String::from("destination")]);
+    }
+
+    #[test]
+    fn event_from_value_custom_endpoint() {
+        let mut event = minima();
+        event["destination"]["region"] = json!("minio");
+        event["destination"]["endpoint"] = json!("http://localhost:9000");
+
+        let actual = Event::from_value(event.clone()).unwrap().destination.remove(0);
+        assert_eq!(actual.endpoint, Some(String::from("http://localhost:9000")));
+        assert!(actual.path_style);
+
+        event["destination"]["endpoint"] = json!("localhost:9000");
+        event["destination"]["path_style"] = json!(false);
+        let expect = vec![String::from("destination.endpoint"), String::from("destination.path_style")];
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), expect);
+    }
+
+    #[test]
+    fn event_from_value_object_options() {
+        let mut event = minima();
+        event["destination"]["options"] = json!({
+            "encryption": "aws:kms",
+            "kms_key_id": "alias/pac-man",
+            "storage_class": "{:secrets:pac-man:class}",
+            "tags": { "chunk": "{:chunks:chunk:index}" }
+        });
+
+        let actual = Event::from_value(event).unwrap().destination.remove(0).options;
+
+        assert_eq!(actual.kms_key_id, Some(String::from("alias/pac-man")));
+        assert_eq!(actual.tags.get("chunk"), Some(&String::from("{:chunks:chunk:index}")));
+    }
+
+    #[test]
+    fn event_from_value_invalid_object_options() {
+        let mut event = minima();
+        event["destination"]["options"] = json!({
+            "encryption": "AES256",
+            "kms_key_id": "alias/pac-man",
+            "acl": "everyone",
+            "metadata": { "count": 1 }
+        });
+
+        let expect = vec![
+            String::from("destination.options.kms_key_id"),
+            String::from("destination.options.acl"),
+            String::from("destination.options.metadata.count")
+        ];
+        let actual = paths(Event::from_value(event).unwrap_err());
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_write_mode() {
+        let mut event = minima();
+        event["destination"]["mode"] = json!("if-changed");
+        assert_eq!(Event::from_value(event.clone()).unwrap().destination[0].mode, Mode::IfChanged);
+
+        event["destination"]["type"] = json!("file");
+        assert_eq!(paths(Event::from_value(event.clone()).unwrap_err()), vec![String::from("destination.mode")]);
+
+        event["destination"]["mode"] = json!("if-absent");
+        assert_eq!(Event::from_value(event).unwrap().destination[0].mode, Mode::IfAbsent);
+    }
+
+    #[test]
+    fn event_from_value_datetime() {
+        let mut event = minima();
+        event["mods"] = json!([{ "name": "datetime", "timezone": "Europe/Berlin" }, { "name": "datetime", "timezone": "Mars/Olympus" }]);
+
+        let expect = vec![String::from("mods[1].timezone")];
+        let actual = paths(Event::from_value(event).unwrap_err());
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn event_from_value_window() {
+        let mut event = minima();
+        event["mods"] = json!([{ "name": "window", "start": "2026-01-01", "step": "1d", "format": "%Y-%m-%d" }]);
+        assert!(Event::from_value(event.clone()).is_ok());
+
+        event["mods"] = json!([{ "name": "window", "start": "2026-01-02", "end": "2026-01-01", "step": "1 day" }]);
+        let expect = vec![String::from("mods[0].end"), String::from("mods[0].step")];
+        assert_eq!(paths(Event::from_value(event.clone()).unwrap_err()), expect);
+
+        event["mods"] = json!([{ "name": "window", "start": "now+99999999d", "step": "99999999999999d" }]);
+        let expect = vec![String::from("mods[0].start"), String::from("mods[0].step")];
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), expect);
+    }
+
+    #[test]
+    fn event_from_value_each() {
+        let mut event = minima();
+        event["mods"] = json!([
+            { "name": "each", "values": ["a", "b"] },
+            { "name": "each", "from": { "type": "file", "collection": "/mnt/efs", "name": "ids.json" }, "pointer": "/ids" }
+        ]);
+        assert!(Event::from_value(event.clone()).is_ok());
+
+        event["mods"] = json!([
+            { "name": "each", "values": [] },
+            { "name": "each" },
+            { "name": "each", "from": { "region": "mars-north-1", "collection": "bucket" } }
+        ]);
+        let expect = vec![
+            String::from("mods[0].values"),
+            String::from("mods[1]"),
+            String::from("mods[2].from.region"),
+            String::from("mods[2].from.name")
+        ];
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), expect);
+    }
+
+    #[test]
+    fn event_from_value_nested_loop() {
+        let mut event = minima();
+        event["loop"] = json!("nested");
+        event["mods"] = json!([
+            { "name": "each", "values": ["a", "b"] },
+            { "name": "chunks", "start": 0, "chunk": { "length": 10 } }
+        ]);
+        event["destination"]["name"] = json!("{:each:value}/{:chunks:chunk:page}.json");
+        assert_eq!(Event::from_value(event.clone()).unwrap().looping, Loop::Nested);
+
+        event["destination"]["name"] = json!("{:each:value}.json");
+        let expect = vec![String::from("destination.name")];
+        assert_eq!(paths(Event::from_value(event.clone()).unwrap_err()), expect);
+
+        event["loop"] = json!("spiral");
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), vec![String::from("loop")]);
+    }
+
+    #[test]
+    fn event_from_value_state() {
+        let mut event = minima();
+        event["state"] = json!({
+            "region": "us-east-1",
+            "collection": "bucket",
+            "job": "orders",
+            "initial": { "since": "2021-01-01" },
+            "update": { "since": "{:datetime}" }
+        });
+        assert_eq!(Event::from_value(event.clone()).unwrap().state.unwrap().name(), "orders.json");
+
+        event["state"] = json!({ "type": "file", "collection": "/mnt/efs", "job": "../orders", "update": {} });
+        let expect = vec![String::from("state.job"), String::from("state.update")];
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), expect);
+    }
+
+    #[test]
+    fn event_from_value_checkpoint() {
+        let mut event = minima();
+        event["checkpoint"] = json!({ "type": "file", "collection": "/mnt/efs", "name": "orders.json", "resume": true });
+        let checkpoint = Event::from_value(event.clone()).unwrap().checkpoint.unwrap();
+        assert_eq!(checkpoint.location.name, "orders.json");
+        assert!(checkpoint.resume);
+
+        event["checkpoint"] = json!({ "region": "us-east-1", "collection": "bucket", "resume": "yes" });
+        let expect = vec![String::from("checkpoint.name"), String::from("checkpoint.resume")];
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), expect);
+    }
+
+    #[test]
+    fn event_from_value_continuation() {
+        let mut event = minima();
+        event["continuation"] = json!({ "chunk": 3, "chunks": 3, "items": 0, "mods": [], "objects": [] });
+        let event = Event::from_value(event).unwrap();
+        assert_eq!(event.continuation.unwrap().chunk, 3);
+        assert_eq!(event.deadline_margin, 10_000);
+
+        let mut event = minima();
+        event["continuation"] = json!("chunk 3");
+        event["deadline_margin"] = json!(-1);
+        let expect = vec![String::from("deadline_margin"), String::from("continuation")];
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), expect);
+    }
+
+    #[test]
+    fn event_from_value_concurrency() {
+        let mut event = minima();
+        event["concurrency"] = json!(8);
+        event["mods"] = json!([{ "name": "chunks", "start": 0, "end": 100, "chunk": { "length": 10 } }]);
+        assert_eq!(Event::from_value(event.clone()).unwrap().concurrency, 8);
+
+        event["mods"] = json!([{ "name": "chunks", "start": 0, "chunk": { "length": 10 } }]);
+        assert_eq!(paths(Event::from_value(event.clone()).unwrap_err()), vec![String::from("concurrency")]);
+
+        event["mods"] = json!([{ "name": "each", "values": ["a", "b"] }]);
+        assert_eq!(paths(Event::from_value(event.clone()).unwrap_err()), vec![String::from("concurrency")]);
+
+        event["mods"] = json!([
+            { "name": "chunks", "start": 0, "end": 100, "chunk": { "length": 10 } },
+            { "name": "cursor", "pointer": "/next" }
+        ]);
+        assert_eq!(paths(Event::from_value(event.clone()).unwrap_err()), vec![String::from("mods[1].name")]);
+
+        event["concurrency"] = json!(0);
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), vec![String::from("concurrency")]);
+    }
+
+    #[test]
+    fn event_from_value_rate() {
+        let mut event = minima();
+        event["source"]["rate"] = json!({ "requests": 1000, "interval": 3_600_000 });
+        event["source"]["delay"] = json!(250);
+        let source = Event::from_value(event.clone()).unwrap().source;
+        assert_eq!(source.rate.unwrap(), Rate { requests: 1000, interval: 3_600_000, burst: None });
+        assert_eq!(source.delay, Some(250));
+
+        event["source"]["rate"] = json!({ "requests": 0, "interval": 0, "burst": -1 });
+        let expect = vec![
+            String::from("source.rate.requests"),
+            String::from("source.rate.burst"),
+            String::from("source.rate.interval")
+        ];
+        assert_eq!(paths(Event::from_value(event).unwrap_err()), expect);
+    }
+}
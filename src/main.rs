@@ -1,21 +1,91 @@
 mod adapters;
+mod checkpoint;
+mod destinations;
+mod error;
+mod event;
 mod mods;
+mod rate;
+mod report;
+mod retry;
+mod state;
+mod status;
+mod until;
 
+use std::time::{
+    Duration,
+    Instant,
+    SystemTime,
+    UNIX_EPOCH
+};
+
+use chrono::{
+    DateTime,
+    Utc
+};
+use futures::{
+    future::join_all,
+    StreamExt
+};
+use hyper::{
+    HeaderMap,
+    StatusCode
+};
 use lambda::{handler_fn, Context};
-use log::{LevelFilter, error};
+use log::{LevelFilter, error, info, warn};
 use simple_logger::SimpleLogger;
-use simple_error::bail;
-use serde_json::Value;
+use serde_json::{
+    json,
+    Value
+};
 
 use adapters::{
+    BodyStream,
     http,
-    s3,
+    measure::{
+        measure,
+        sha256
+    },
     to_uri
 };
+use checkpoint::Progress;
+use destinations::{
+    Dest,
+    HASH_METADATA,
+    Target,
+    put_all,
+    to_destinations
+};
+use error::{
+    Error as PacManError,
+    Failure,
+    Stage
+};
+use event::{
+    Action,
+    Destination as DestinationConfig,
+    Event,
+    Kind,
+    Loop,
+    Mode,
+    Source
+};
 use mods::{
     Modifiers,
+    Page,
+    State,
     to_mods
 };
+use report::{
+    ObjectReport,
+    Report,
+    RequestReport,
+    Skip,
+    SkipReport,
+    Stop,
+    redact
+};
+use rate::Limiter;
+use until::Condition;
 
 type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
 
@@ -31,112 +101,760 @@ async fn main() -> Result<(), Error> {
     Ok(())
 }
 
-async fn func(event: Value, _: Context) -> Result<Value, Error> {
+async fn func(value: Value, context: Context) -> Result<Report, Failure> {
+    let mut report = Report::default();
+    match run(value, to_deadline(context.deadline), &mut report).await {
+        Ok(()) => Ok(report),
+        Err(mut failure) => {
+            report.stop = Some(Stop::Error);
+            failure.report = Some(report);
+            error!("{}", failure);
+            Err(failure)
+        }
+    }
+}
+
+/// Lambda deadline (milliseconds since epoch) as instant or None when unknown
+fn to_deadline(deadline: u64) -> Option<Instant> {
+    if deadline == 0 { return None; }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let remaining = Duration::from_millis(deadline).checked_sub(now).unwrap_or_default();
+    Some(Instant::now() + remaining)
+}
+
+async fn run(value: Value, deadline: Option<Instant>, report: &mut Report) -> Result<(), Failure> {
+    // Validate Event (reports every problem before any request is made)
+    let event = Event::from_value(value.clone())
+        .map_err(|e| PacManError::from(e).at(Stage::Event, None))?;
+
+    // Continue run (continuation event) or resume interrupted run from checkpoint (modifiers continue where the
+    // last chunk stored left them)
+    let checkpoint_failure = |e: PacManError| e.at(Stage::Checkpoint, None);
+    let progress = match (&event.continuation, event.checkpoint.as_ref().filter(|c| c.resume)) {
+        (Some(progress), _) => Some(progress.clone()),
+        (_, Some(config)) => checkpoint::load(config).await.map_err(checkpoint_failure)?,
+        _ => None
+    };
+
+    // Run start time is pinned once per run (continued and resumed runs keep it)
+    let started = match progress.as_ref().and_then(|p| p.started.as_ref()) {
+        Some(started) => DateTime::parse_from_rfc3339(started)
+            .map_err(|e| checkpoint_failure(PacManError::modifier("checkpoint", format!("invalid start time: {}", e))))?
+            .with_timezone(&Utc),
+        _ => Utc::now()
+    };
+
     // Bootstrap Modules
-    let mods_config = event["mods"].as_array();
-    let modifiers = to_mods(mods_config);
-    let mut mods = Modifiers::new(modifiers);
+    let mut modifiers = to_mods(&event.mods, started)
+        .await
+        .map_err(|e| e.at(Stage::Modifiers, None))?;
+
+    // Load job state (read by `{:state:<key>}`)
+    let mut values = None;
+    if let Some(config) = &event.state {
+        let loaded = state::load(config).await.map_err(|e| e.at(Stage::State, None))?;
+        modifiers.push(Box::new(State::new(loaded.clone())));
+        values = Some(loaded);
+    }
+    let mut updated = None;
+    let mut mods = match event.looping {
+        Loop::Lockstep => Modifiers::new(modifiers),
+        Loop::Nested => Modifiers::nested(modifiers)
+    };
+
+    // Items seen across pages of the (inner) loop (source `until` total condition)
+    let mut items: u64 = 0;
+    let mut chunk: u64 = 0;
+    if let Some(progress) = progress {
+        mods.restore(&progress.mods).map_err(checkpoint_failure)?;
+        info!("resuming run from chunk {}", progress.chunk);
+        chunk = progress.chunk;
+        items = progress.items;
+        report.chunks = progress.chunks;
+        report.objects = progress.objects;
+        updated = progress.state;
+    }
+
+    // Bootstrap Destinations (body is hashed when any destination requires digest)
+    let destinations = to_destinations(&event.destination)
+        .map_err(|e| e.at(Stage::Destination, None))?;
+    let digest = event.destination.iter().any(|d| d.digest);
 
     // Get chunks modifier bytes (if active)
-    let mut chunking = false;
+    let chunking = mods.iterates();
     let mut bytes: i64 = 0;
     if let Some(chunks) = mods.find("chunks") {
-        chunking = true;
-        bytes = chunks.option("bytes").unwrap().parse::<i64>().unwrap();
+        bytes = chunks
+            .option("bytes")
+            .unwrap_or_default()
+            .parse::<i64>()
+            .map_err(|e| PacManError::modifier("chunks", e).at(Stage::Modifiers, None))?;
     }
 
+    // Chunks fetched at once (every chunk of a batch is prepared before modifiers observe responses)
+    let concurrency = if chunking { event.concurrency } else { 1 };
+    let buffer = mods.requires_body() || event.source.until.is_some();
+
+    // Requests (and retries) of every chunk share the source rate limit
+    let limiter = event.source.rate.as_ref().map(Limiter::new);
+    let delay = Duration::from_millis(event.source.delay.unwrap_or_default());
+    let mut first = true;
+
+    // Longest batch of this invocation (a batch isn't started unless it can complete before the deadline)
+    let margin = Duration::from_millis(event.deadline_margin);
+    let mut longest: Option<Duration> = None;
     loop {
-        // Get Stream from Source
-        let mut headers: Vec<(String, String)> = Vec::new();
-        if let Some(source_headers) = event["source"].get("headers") {
-            for (header, values) in source_headers.as_object().unwrap() {
-                for value in values.as_array().unwrap() {
-                    let value = String::from(value.as_str().unwrap());
-                    let value = mods.reduce(value).await;
-                    headers.push((header.clone(), value));
+        // Stop when modifiers have nothing left to iterate
+        if mods.exhausted() {
+            report.stop = Some(Stop::End);
+            break;
+        }
+
+        // Stop before the Lambda deadline (every invocation completes at least one batch)
+        if let (Some(deadline), Some(longest)) = (deadline, longest) {
+            if Instant::now() + delay + longest + margin > deadline {
+                info!("lambda deadline is near; stopping before chunk {}", chunk);
+                report.stop = Some(Stop::Deadline);
+                break;
+            }
+        }
+        // Fixed delay between pages
+        if !first && delay > Duration::default() { tokio::time::delay_for(delay).await; }
+        first = false;
+        let batch_started = Instant::now();
+
+        // Reduce requests and object names of every chunk of the batch (names are deterministic per chunk)
+        let mut batch = vec![];
+        loop {
+            batch.push(prepare(&mut mods, &event, &destinations, chunk + batch.len() as u64).await?);
+            if batch.len() == concurrency { break; }
+            mods.advance();
+            if mods.exhausted() { break; }
+        }
+
+        // Get Streams from Source and put them into Destinations
+        let fetches = batch.iter().map(|prepared| fetch(&event, prepared, limiter.as_ref(), bytes, buffer, digest));
+        let results = join_all(fetches).await;
+
+        // Evaluate responses in chunk order; objects of chunks fetched after a chunk ending (or failing) the run are
+        // reported without evaluating their responses (they may have replaced previous objects, so they're kept)
+        let mut stop = None;
+        let mut failure = None;
+        let mut observed = false;
+        for (prepared, result) in batch.iter().zip(results) {
+            if stop.is_some() || failure.is_some() {
+                if let Ok(fetched) = result { report_unevaluated(report, fetched); }
+                continue;
+            }
+            let mut fetched = match result {
+                Ok(fetched) => fetched,
+                Err(e) => {
+                    failure = Some(e);
+                    continue;
+                }
+            };
+            let chunk = prepared.chunk;
+            let status = fetched.status;
+            report.requests.push(fetched.request);
+
+            // Objects stored before a destination failed are reported (they replaced previous objects)
+            if let Some(stored) = fetched.stored.as_mut() {
+                if let Some(e) = stored.failed.take() {
+                    let stored = std::mem::take(&mut stored.objects);
+                    match fetched.action {
+                        Some(Action::Store) => report.errors.extend(stored),
+                        _ => report.objects.extend(stored)
+                    }
+                    failure = Some(e.at(Stage::Destination, Some(chunk)));
+                    continue;
+                }
+            }
+            match fetched.action {
+                Some(Action::Fail) => failure = Some(PacManError::status(status).at(Stage::Source, Some(chunk))),
+                Some(Action::Stop) => {
+                    info!("source responded {}; stopping", status);
+                    stop = Some(Stop::Status);
+                }
+                Some(action @ Action::Skip) | Some(action @ Action::Store) => {
+                    if action == Action::Skip { warn!("source responded {}; skipping chunk {}", status, chunk); }
+                    if let Some(stored) = fetched.stored {
+                        let name = &prepared.errors[0].name;
+                        warn!("source responded {}; stored body of chunk {} to {}", status, chunk, name);
+                        report.errors.extend(stored.objects);
+                        report.skipped.extend(stored.skipped);
+                    }
+                    // Pages taken from responses can't continue past a response without a next page
+                    if mods.paginates() {
+                        warn!("source responded {}; no next page after chunk {}; stopping", status, chunk);
+                        stop = Some(Stop::Unpaginated);
+                    }
+                }
+                None if fetched.short => stop = Some(Stop::Bytes),
+                None => {
+                    // Modifiers paginating with responses observe page before advancing
+                    mods.observe(&Page { uri: &prepared.uri, headers: &fetched.headers, body: fetched.json.as_ref() });
+                    let json = fetched.json.as_ref();
+                    let condition = event.source.until
+                        .as_ref()
+                        .and_then(|until| until::reached(until, json, &mut items));
+                    if let Some(Condition::Empty) = condition {
+                        info!("source response of chunk {} has no items; stopping", chunk);
+                        stop = Some(Stop::Body);
+                        continue;
+                    }
+
+                    if let Some(stored) = fetched.stored {
+                        report.chunks += 1;
+                        report.objects.extend(stored.objects);
+                        report.skipped.extend(stored.skipped);
+
+                        // State values of the last stored chunk are persisted once the run completes
+                        if prepared.update.is_some() { updated = prepared.update.clone(); }
+                        observed = true;
+
+                        if let Some(condition) = condition {
+                            info!("source response of chunk {} met {:?} condition; stopping", chunk, condition);
+                            stop = Some(Stop::Body);
+                        } else if chunking && stored.bytes < bytes {
+                            stop = Some(Stop::Bytes);
+                        }
+                    }
                 }
             }
         }
-        let uri = source_to_uri(&event["source"]);
-        let uri = mods.reduce(uri).await;
-        let (headers, body) = http::get_stream(&headers, &uri).await;
-        let content_type = headers.get("content-type").unwrap().to_str().unwrap();
-        let content_length: Option<i64> = match headers.get("content-length") {
-            Some(value) => {
-                let content_length: i64 = value
-                    .to_str()
-                    .unwrap()
-                    .parse()
-                    .unwrap();
-                if content_length < bytes { break; }
-                Some(content_length)
+        if let Some(failure) = failure { return Err(failure); }
+
+        // Modifiers paginating with responses advance to the page returned before state is reduced (e.g. the
+        // `{:cursor:next}` token of the stored page). The last page returns no token, so empty values keep the
+        // value of the previous page (or the persisted value)
+        let mut advanced = None;
+        if observed && mods.paginates() && event.state.is_some() {
+            advanced = Some(mods.advance());
+            let reduced = reduce_update(&mut mods, &event, chunk).await?.unwrap_or_default();
+            let mut values = updated.take().unwrap_or_default();
+            values.extend(reduced.into_iter().filter(|(_, value)| !value.is_empty()));
+            updated = Some(values);
+        }
+
+        if let Some(stop) = stop {
+            // nested loops end the inner loop only (e.g. 404 for one of many accounts)
+            if !mods.end_loop() {
+                report.stop = Some(stop);
+                break;
             }
-            _ => None
-        };
+            info!("chunk {} ended inner loop", chunk);
+            items = 0;
+            chunk += 1;
+        } else {
+            if !chunking {
+                report.stop = Some(Stop::End);
+                break;
+            }
+
+            // Advance modifiers in event that they track chunks (requests)
+            if advanced.unwrap_or_else(|| mods.advance()) { items = 0; }
+            chunk += batch.len() as u64;
+        }
+
+        // Checkpoint progress so an interrupted run can resume with the next chunk
+        if let Some(config) = &event.checkpoint {
+            let progress = to_progress(&mods, chunk, items, report, &updated, started);
+            checkpoint::save(config, &progress).await.map_err(|e| e.at(Stage::Checkpoint, Some(chunk)))?;
+        }
+        longest = longest.max(Some(batch_started.elapsed()));
+    }
+
+    // Stopped before the deadline; the continuation event continues the run (state and checkpoint are kept)
+    if let Some(Stop::Deadline) = report.stop {
+        let mut continuation = value;
+        continuation["continuation"] = json!(to_progress(&mods, chunk, items, report, &updated, started));
+        report.continuation = Some(continuation);
+        return Ok(());
+    }
+
+    // Persist job state (runs storing no chunks leave state unchanged)
+    if let (Some(config), Some(mut values), Some(updated)) = (&event.state, values, updated) {
+        values.extend(updated);
+        state::save(config, &values).await.map_err(|e| e.at(Stage::State, None))?;
+        info!("persisted state of job {}", config.job);
+        report.state = Some(values);
+    }
 
-        // Put Stream into Destination
-        let region = event["destination"]["region"].as_str().unwrap();
-        let collection = event["destination"]["collection"].as_str().unwrap();
-        let name = mods.reduce(
-            String::from(event["destination"]["name"].as_str().unwrap())
-        ).await;
-        s3::put_object(
-            region,
-            collection,
-            name.as_str(),
-            content_type,
-            content_length,
-            body
-        ).await;
-
-        if chunking {
-            // todo - replace head call with stream byte count
-            let object = s3::get_object_head(
-                region,
-                collection,
-                name.as_str()
-            ).await;
-            if object.content_length.unwrap() < bytes { break; }
-        } else { break; }
-
-        // Advance modifiers in event that they track chunks (requests)
-        mods.advance();
-    }
-
-    Ok(event)
+    // Completed runs have nothing to resume
+    if let Some(config) = &event.checkpoint {
+        checkpoint::clear(config).await.map_err(|e| e.at(Stage::Checkpoint, None))?;
+    }
+
+    Ok(())
 }
 
-/// Event Source to URI  - Checks for optional parts
-fn source_to_uri(source: &Value) -> String {
-    let scheme = source["scheme"].as_str().unwrap();
+/// Progress of run to continue with chunk
+fn to_progress(
+    mods: &Modifiers,
+    chunk: u64,
+    items: u64,
+    report: &Report,
+    updated: &Option<state::Values>,
+    started: DateTime<Utc>
+) -> Progress {
+    Progress {
+        chunk,
+        chunks: report.chunks,
+        items,
+        mods: mods.checkpoint(),
+        objects: report.objects.clone(),
+        state: updated.clone(),
+        started: Some(started.to_rfc3339())
+    }
+}
+
+/// Request and objects of a chunk reduced by modifiers before they advance
+struct Prepared<'a> {
+    chunk: u64,
+    headers: Vec<(String, String)>,
+    uri: String,
+    /// Uri with secrets redacted (reported)
+    redacted: String,
+    body: Option<String>,
+    targets: Vec<Target<'a>>,
+    /// Targets of error response bodies (status `store` action)
+    errors: Vec<Target<'a>>,
+    /// Job state values of chunk
+    update: Option<state::Values>
+}
 
-    let username = source.get("username");
-    let password = source.get("password");
-    let credentials = match (username, password) {
-        (Some(u), Some(p)) => {
-            Some((u.as_str().unwrap(), p.as_str().unwrap()))
+/// Reduce request, object names and job state values of chunk
+async fn prepare<'a>(
+    mods: &mut Modifiers,
+    event: &Event,
+    destinations: &'a [Dest],
+    chunk: u64
+) -> Result<Prepared<'a>, Failure> {
+    let source_failure = |e: PacManError| e.at(Stage::Source, Some(chunk));
+    let mut headers: Vec<(String, String)> = Vec::new();
+    if let Some(source_headers) = &event.source.headers {
+        for (header, values) in source_headers {
+            for value in values {
+                let value = mods.reduce(value.clone()).await.map_err(source_failure)?;
+                headers.push((header.clone(), value));
+            }
+        }
+    }
+    // Modifiers following response links replace event source uri after first request
+    let uri = match mods.uri() {
+        Some(uri) => uri,
+        _ => mods.reduce(source_to_uri(&event.source)).await.map_err(source_failure)?
+    };
+    let body = match &event.source.body {
+        Some(Value::String(body)) => Some(mods.reduce(body.clone()).await.map_err(source_failure)?),
+        Some(body) => {
+            if !headers.iter().any(|(header, _)| header.eq_ignore_ascii_case("content-type")) {
+                headers.push((String::from("content-type"), String::from("application/json")));
+            }
+            let body = mods.reduce_value(body.clone()).await.map_err(source_failure)?;
+            Some(body.to_string())
         }
         _ => None
     };
 
-    let hostname = source["hostname"].as_str().unwrap();
+    let destination_failure = |e: PacManError| e.at(Stage::Destination, Some(chunk));
+    let targets = reduce_targets(mods, destinations, &event.destination, None)
+        .await
+        .map_err(destination_failure)?;
+    let errors = match event.source.status.as_ref().and_then(|s| s.errors.as_deref()) {
+        Some(errors) => reduce_targets(mods, destinations, &event.destination, Some(errors))
+            .await
+            .map_err(destination_failure)?,
+        _ => vec![]
+    };
 
-    let port = match source.get("port") {
-        Some(port) => port.as_u64(),
-        _ => None
+    // State of modifiers paginating with responses is reduced once the page is observed (see `run`)
+    let update = if mods.paginates() { None } else { reduce_update(mods, event, chunk).await? };
+
+    let redacted = redact(&uri, &mods.sensitive());
+    Ok(Prepared { chunk, headers, uri, redacted, body, targets, errors, update })
+}
+
+/// Reduce job state `update` values (None without state)
+async fn reduce_update(mods: &mut Modifiers, event: &Event, chunk: u64) -> Result<Option<state::Values>, Failure> {
+    let config = match &event.state {
+        Some(config) => config,
+        _ => return Ok(None)
     };
-    let path = match source.get("path") {
-        Some(v) => v.as_str(),
-        _ => None
+    let mut reduced = state::Values::new();
+    for (key, value) in config.update.iter() {
+        let value = mods.reduce(value.clone()).await.map_err(|e| e.at(Stage::State, Some(chunk)))?;
+        reduced.insert(key.clone(), value);
+    }
+    Ok(Some(reduced))
+}
+
+/// Response of a chunk and objects stored from it (stop conditions are evaluated in chunk order by `run`)
+struct Fetched {
+    request: RequestReport,
+    status: StatusCode,
+    action: Option<Action>,
+    headers: HeaderMap,
+    /// Response body parsed as JSON (when buffered)
+    json: Option<Value>,
+    /// Response was smaller than chunks `bytes` threshold (not stored)
+    short: bool,
+    /// Objects stored from response body (accepted response or body of status `store` action)
+    stored: Option<Stored>
+}
+
+/// Fetch chunk from source and store the response body
+///
+/// Bodies of responses that end pagination before storing (`bytes` threshold or no `until` items) aren't stored
+async fn fetch(
+    event: &Event,
+    prepared: &Prepared<'_>,
+    limiter: Option<&Limiter>,
+    bytes: i64,
+    buffer: bool,
+    digest: bool
+) -> Result<Fetched, Failure> {
+    let chunk = prepared.chunk;
+    let source_failure = |e: PacManError| e.at(Stage::Source, Some(chunk));
+    let method = event.source.method.as_deref().unwrap_or("GET");
+    let started = Instant::now();
+    let (response, attempts) = retry::send(event.source.retry.as_ref(), || async move {
+        if let Some(limiter) = limiter { limiter.acquire().await; }
+        http::get_stream(method, &prepared.headers, &prepared.uri, prepared.body.clone()).await
+    }).await;
+    let (status, headers, mut body) = response.map_err(source_failure)?;
+    let request = RequestReport {
+        uri: prepared.redacted.clone(),
+        status: status.as_u16(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        attempts
     };
-    let params = match source.get("params") {
-        Some(v) => v.as_object(),
+    let content_type = match headers.get("content-type") {
+        Some(value) => value.to_str().map_err(|e| source_failure(PacManError::source(e)))?,
+        _ => "application/octet-stream"
+    };
+    let content_type = String::from(content_type);
+    let mut content_length: Option<i64> = match headers.get("content-length") {
+        Some(value) => {
+            let content_length: i64 = value
+                .to_str()
+                .map_err(|e| source_failure(PacManError::source(e)))?
+                .parse()
+                .map_err(|e| source_failure(PacManError::source(e)))?;
+            Some(content_length)
+        }
         _ => None
     };
-    let fragment = match source.get("fragment") {
-        Some(v) => v.as_str(),
+
+    let action = status::action(event.source.status.as_ref(), status);
+    let mut fetched = Fetched { request, status, action, headers, json: None, short: false, stored: None };
+
+    // Put Stream into Destination
+    let destination_failure = |e: PacManError| e.at(Stage::Destination, Some(chunk));
+    let targets = match action {
+        Some(Action::Store) => prepared.errors.clone(),
+        Some(_) => return Ok(fetched),
+        None => {
+            if let Some(content_length) = content_length {
+                if content_length < bytes {
+                    fetched.short = true;
+                    return Ok(fetched);
+                }
+            }
+            if buffer {
+                let buffered = adapters::buffer(body)
+                    .await
+                    .map_err(|e| source_failure(PacManError::source(e)))?;
+                fetched.json = serde_json::from_slice(&buffered).ok();
+                if fetched.json.is_none() { warn!("source response of chunk {} is not JSON", chunk); }
+                let until = event.source.until.as_ref();
+                if matches!(until, Some(until) if until::is_empty(until, fetched.json.as_ref())) {
+                    return Ok(fetched);
+                }
+                content_length = Some(buffered.len() as i64);
+                body = adapters::to_stream(buffered);
+            }
+            prepared.targets.clone()
+        }
+    };
+    let stored = store(targets, digest, &content_type, content_length, body)
+        .await
+        .map_err(destination_failure)?;
+    fetched.stored = Some(stored);
+    Ok(fetched)
+}
+
+/// Report request and objects of a chunk fetched after the chunk ending the run
+fn report_unevaluated(report: &mut Report, fetched: Fetched) {
+    report.requests.push(fetched.request);
+    if let Some(stored) = fetched.stored {
+        match fetched.action {
+            Some(Action::Store) => report.errors.extend(stored.objects),
+            _ => report.objects.extend(stored.objects)
+        }
+        report.skipped.extend(stored.skipped);
+    }
+}
+
+/// Reduce object names (destination name or errors name) and object options of every destination
+async fn reduce_targets<'a>(
+    mods: &mut Modifiers,
+    destinations: &'a [Dest],
+    config: &[DestinationConfig],
+    errors: Option<&str>
+) -> Result<Vec<Target<'a>>, PacManError> {
+    let mut targets = vec![];
+    for (destination, config) in destinations.iter().zip(config) {
+        let name = mods.reduce(String::from(errors.unwrap_or(&config.name))).await?;
+        let value = mods.reduce_value(json!(config.options)).await?;
+        let options = serde_json::from_value(value).map_err(|e| match config.kind {
+            Kind::S3 => PacManError::Destination { message: e.to_string(), code: None, status: None },
+            Kind::File => PacManError::file(&name, e)
+        })?;
+        targets.push(Target { destination, name, options, mode: config.mode });
+    }
+    Ok(targets)
+}
+
+/// Objects stored (or skipped by write mode) and bytes read from body stream
+struct Stored {
+    objects: Vec<ObjectReport>,
+    skipped: Vec<SkipReport>,
+    bytes: i64,
+    /// First destination that failed (objects stored in other destinations are kept and reported)
+    failed: Option<PacManError>
+}
+
+/// Store body stream in every target measuring bytes (and hashes) while streaming
+///
+/// Targets are skipped when their write mode finds the object exists (`if-absent`) or is unchanged (`if-changed`)
+async fn store(
+    targets: Vec<Target<'_>>,
+    digest: bool,
+    content_type: &str,
+    mut content_length: Option<i64>,
+    mut body: BodyStream
+) -> Result<Stored, PacManError> {
+    // Hash is required before writing to compare with objects written before (bodies are buffered up to
+    // the part size so memory stays bound; larger bodies are put unconditionally)
+    let mut hash = None;
+    let changed = targets.iter().filter(|t| t.mode == Mode::IfChanged);
+    if let Some(limit) = changed.filter_map(|t| t.destination.part_size()).min() {
+        if !matches!(content_length, Some(length) if length > limit as i64) {
+            let (buffered, rest) = adapters::buffer_limited(body, limit).await.map_err(PacManError::source)?;
+            body = match rest {
+                Some(rest) => Box::new(adapters::to_stream(buffered).chain(rest)),
+                _ => {
+                    hash = Some(sha256(&buffered));
+                    content_length = Some(buffered.len() as i64);
+                    adapters::to_stream(buffered)
+                }
+            };
+        }
+        if hash.is_none() { info!("body is larger than part size; if-changed objects are put unconditionally"); }
+    }
+
+    let mut writes = vec![];
+    let mut skipped = vec![];
+    for mut target in targets {
+        let skip = match target.mode {
+            Mode::Overwrite => None,
+            Mode::IfAbsent => target.destination.stat(&target.name).await?.map(|stat| (stat, Skip::Exists)),
+            Mode::IfChanged => match &hash {
+                Some(hash) => {
+                    let stat = target.destination.stat(&target.name).await?;
+                    target.options.metadata.insert(String::from(HASH_METADATA), hash.clone());
+                    stat.filter(|stat| stat.metadata.get(HASH_METADATA) == Some(hash)).map(|stat| (stat, Skip::Unchanged))
+                }
+                _ => None
+            }
+        };
+        match skip {
+            Some((stat, reason)) => skipped.push(SkipReport {
+                bucket: String::from(target.destination.collection()),
+                key: target.name,
+                reason,
+                bytes: stat.bytes,
+                e_tag: stat.e_tag
+            }),
+            _ => writes.push(target)
+        }
+    }
+
+    let (mut body, meter) = measure(body, digest);
+    let results = if writes.is_empty() {
+        // body is read (not stored) so bytes threshold still applies
+        while let Some(bytes) = body.next().await { bytes.map_err(PacManError::source)?; }
+        vec![]
+    } else {
+        put_all(&writes, content_type, content_length, body).await
+    };
+    let measurement = meter.finish();
+    let mut objects = vec![];
+    let mut failed = None;
+    for (target, result) in writes.into_iter().zip(results) {
+        let collection = String::from(target.destination.collection());
+        match result {
+            Ok(e_tag) => objects.push(ObjectReport {
+                bucket: collection,
+                key: target.name,
+                bytes: Some(measurement.bytes),
+                content_type: String::from(content_type),
+                e_tag,
+                md5: measurement.md5.clone(),
+                sha256: measurement.sha256.clone()
+            }),
+            Err(e) => {
+                warn!("failed to put {} to {}: {}", target.name, collection, e);
+                if failed.is_none() { failed = Some(e); }
+            }
+        }
+    }
+    Ok(Stored { objects, skipped, bytes: measurement.bytes, failed })
+}
+
+/// Event Source to URI  - Checks for optional parts
+fn source_to_uri(source: &Source) -> String {
+    let credentials = match (&source.username, &source.password) {
+        (Some(u), Some(p)) => Some((u.as_str(), p.as_str())),
         _ => None
     };
 
-    to_uri(scheme, credentials, hostname, port, path, params, fragment)
-}
\ No newline at end of file
+    to_uri(
+        source.scheme.as_str(),
+        credentials,
+        source.hostname.as_str(),
+        source.port,
+        source.path.as_deref(),
+        source.params.as_ref(),
+        source.fragment.as_deref()
+    )
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+
+    use hyper::{
+        Body,
+        Request,
+        Response,
+        Server,
+        service::{
+            make_service_fn,
+            service_fn
+        }
+    };
+
+    use adapters::{
+        file::read_file,
+        temp_directory
+    };
+
+    /// Source responding with status of request path
+    fn source(status: fn(&str) -> StatusCode) -> SocketAddr {
+        let service = make_service_fn(move |_| async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| async move {
+                let response = Response::builder().status(status(request.uri().path())).body(Body::from("{}"));
+                Ok::<_, Infallible>(response.unwrap())
+            }))
+        });
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(service);
+        let address = server.local_addr();
+        tokio::spawn(server);
+        address
+    }
+
+    /// Nested run of accounts whose pages all respond 404 (every inner loop ends by a `stop` action)
+    fn nested(address: SocketAddr, directory: &str) -> Value {
+        json!({
+            "loop": "nested",
+            "mods": [
+                { "name": "each", "values": ["acme", "globex", "initech"] },
+                { "name": "chunks", "start": 0, "end": 100, "chunk": { "length": 10 } }
+            ],
+            "source": {
+                "scheme": "http",
+                "hostname": "127.0.0.1",
+                "port": address.port(),
+                "path": "/{:each:value}",
+                "status": { "actions": { "404": "stop" } }
+            },
+            "destination": {
+                "type": "file",
+                "collection": directory,
+                "name": "{:each:value}/{:chunks:chunk:index}.json"
+            },
+            "checkpoint": {
+                "type": "file",
+                "collection": directory,
+                "name": "checkpoint.json"
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn run_nested_end_loop_every_inner_loop() {
+        let directory = temp_directory();
+        let event = nested(source(|_| StatusCode::NOT_FOUND), directory.path());
+
+        let mut report = Report::default();
+        run(event, None, &mut report).await.unwrap();
+
+        assert_eq!(report.stop, Some(Stop::End));
+        assert_eq!(report.requests.len(), 3);
+        assert_eq!(read_file(directory.path(), "checkpoint.json").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn run_nested_end_loop_checkpoints_and_stops_before_deadline() {
+        let directory = temp_directory();
+        let event = nested(source(|_| StatusCode::NOT_FOUND), directory.path());
+
+        // the deadline has passed, so the run stops once the duration of a batch is known
+        let mut report = Report::default();
+        run(event, Some(Instant::now()), &mut report).await.unwrap();
+
+        assert_eq!(report.stop, Some(Stop::Deadline));
+        assert_eq!(report.requests.len(), 1);
+        assert_eq!(report.continuation.unwrap()["continuation"]["chunk"], json!(1));
+
+        let checkpoint = read_file(directory.path(), "checkpoint.json").await.unwrap().unwrap();
+        let progress: Progress = serde_json::from_slice(&checkpoint).unwrap();
+        assert_eq!(progress.chunk, 1);
+    }
+
+    #[tokio::test]
+    async fn run_concurrent_stop_keeps_later_chunks() {
+        let directory = temp_directory();
+        let address = source(|path| if path == "/1" { StatusCode::NOT_FOUND } else { StatusCode::OK });
+        let event = json!({
+            "concurrency": 3,
+            "mods": [{ "name": "chunks", "start": 0, "end": 30, "chunk": { "length": 10 } }],
+            "source": {
+                "scheme": "http",
+                "hostname": "127.0.0.1",
+                "port": address.port(),
+                "path": "/{:chunks:chunk:index}",
+                "status": { "actions": { "404": "stop" } }
+            },
+            "destination": {
+                "type": "file",
+                "collection": directory.path(),
+                "name": "{:chunks:chunk:index}.json"
+            }
+        });
+
+        let mut report = Report::default();
+        run(event, None, &mut report).await.unwrap();
+
+        let keys: Vec<&str> = report.objects.iter().map(|object| object.key.as_str()).collect();
+        assert_eq!(report.stop, Some(Stop::Status));
+        assert_eq!(report.chunks, 1);
+        assert_eq!(report.requests.len(), 3);
+        assert_eq!(keys, vec!["0.json", "2.json"]);
+        assert!(read_file(directory.path(), "2.json").await.unwrap().is_some());
+    }
+}
@@ -1,7 +1,24 @@
-use crate::mods::Modifier;
+use serde::{
+    Deserialize,
+    Serialize
+};
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::mods::{
+    Modifier,
+    restored
+};
 
 pub const NAME: &str = "chunks";
 
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    chunk_start: u64,
+    chunk_end: u64,
+    chunk_index: u64
+}
+
 /// Chunks API responses by providing iterator variables
 pub struct Chunks {
     start: u64,
@@ -47,25 +64,19 @@ impl Modifier for Chunks {
         }
     }
 
-    fn modify(&mut self, params: Vec<&str>) -> Option<String> {
-        if params[0].eq("chunk") {
-            match params[1] {
-                "start" => {
-                    return Some(format!("{}", self.chunk_start));
-                }
-                "end" => {
-                    return Some(format!("{}", self.chunk_end));
-                }
-                "index" => {
-                    return Some(format!("{}", self.chunk_index));
-                },
-                "page" => {
-                    return Some(format!("{}", self.chunk_index + 1));
-                }
-                _ => return None
-            }
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+        match params.first() {
+            Some(&"chunk") => match params.get(1) {
+                Some(&"start") => Ok(Some(format!("{}", self.chunk_start))),
+                Some(&"end") => Ok(Some(format!("{}", self.chunk_end))),
+                Some(&"index") => Ok(Some(format!("{}", self.chunk_index))),
+                Some(&"page") => Ok(Some(format!("{}", self.chunk_index + 1))),
+                Some(_) => Ok(None),
+                _ => Err(Error::modifier(NAME, "missing chunk variable (start, end, index or page)"))
+            },
+            Some(_) => Ok(None),
+            _ => Err(Error::modifier(NAME, "missing variable (e.g. {:chunks:chunk:index})"))
         }
-        None
     }
 
     fn advance(&mut self) {
@@ -83,6 +94,32 @@ impl Modifier for Chunks {
 
         self.chunk_index += 1;
     }
+
+    fn reset(&mut self) {
+        *self = Chunks::new(self.start, self.chunk_length, self.end, &self.bytes);
+    }
+
+    fn exhausted(&self) -> bool {
+        self.chunk_start >= self.chunk_end
+    }
+
+    fn iterates(&self) -> bool { true }
+
+    fn checkpoint(&self) -> Option<Value> {
+        serde_json::to_value(Checkpoint {
+            chunk_start: self.chunk_start,
+            chunk_end: self.chunk_end,
+            chunk_index: self.chunk_index
+        }).ok()
+    }
+
+    fn restore(&mut self, state: &Value) -> Result<(), Error> {
+        let checkpoint: Checkpoint = restored(NAME, state)?;
+        self.chunk_start = checkpoint.chunk_start;
+        self.chunk_end = checkpoint.chunk_end;
+        self.chunk_index = checkpoint.chunk_index;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -120,20 +157,40 @@ mod tests {
     }
 
     #[tokio::test]
-    #[should_panic]
     async fn chunks_modify_no_param() {
         let start = 0;
         let chunk_length = 10;
         let end = Some(100);
         let bytes = "0";
 
-        let expect = None;
-
         let mut chunks = Chunks::new(start, chunk_length, end, bytes);
 
         let actual = chunks.modify(vec![]);
 
-        assert_eq!(actual, expect);
+        assert!(actual.is_err());
+    }
+
+    #[tokio::test]
+    async fn chunks_modify_no_chunk_param() {
+        let start = 0;
+        let chunk_length = 10;
+        let end = Some(100);
+        let bytes = "0";
+
+        let mut chunks = Chunks::new(start, chunk_length, end, bytes);
+
+        let actual = chunks.modify(vec!["chunk"]);
+
+        assert!(actual.is_err());
+    }
+
+    #[tokio::test]
+    async fn chunks_reduce_short_placeholders() {
+        let mut mods = crate::mods::Modifiers::new(vec![Box::new(Chunks::new(0, 10, Some(100), "0"))]);
+
+        assert!(mods.reduce(String::from("page-{:chunks}")).await.is_err());
+        assert!(mods.reduce(String::from("page-{:chunks:chunk}")).await.is_err());
+        assert_eq!(mods.reduce(String::from("page-{:chunks:chunk:page}")).await.unwrap(), "page-1");
     }
 
     #[tokio::test]
@@ -147,7 +204,7 @@ mod tests {
 
         let mut chunks = Chunks::new(start, chunk_length, end, bytes);
 
-        let actual = chunks.modify(vec!["unknown"]);
+        let actual = chunks.modify(vec!["unknown"]).unwrap();
 
         assert_eq!(actual, expect);
     }
@@ -161,10 +218,10 @@ mod tests {
 
         let mut chunks = Chunks::new(start, chunk_length, end, bytes);
 
-        let actual_start = chunks.modify(vec!["chunk", "start"]);
-        let actual_end = chunks.modify(vec!["chunk", "end"]);
-        let actual_index = chunks.modify(vec!["chunk", "index"]);
-        let actual_page = chunks.modify(vec!["chunk", "page"]);
+        let actual_start = chunks.modify(vec!["chunk", "start"]).unwrap();
+        let actual_end = chunks.modify(vec!["chunk", "end"]).unwrap();
+        let actual_index = chunks.modify(vec!["chunk", "index"]).unwrap();
+        let actual_page = chunks.modify(vec!["chunk", "page"]).unwrap();
 
         assert_eq!(actual_start, Some(String::from("0")));
         assert_eq!(actual_end, Some(String::from("10")));
@@ -184,7 +241,7 @@ mod tests {
         let mut iteration_count = 0;
         let expect_iteration_count = 10;
         loop {
-            if chunks.chunk_start >= chunks.chunk_end { break; }
+            if chunks.exhausted() { break; }
             iteration_count += 1;
             chunks.advance();
         }
@@ -215,4 +272,17 @@ mod tests {
         assert_eq!(chunks.chunk_start, 105);
         assert_eq!(chunks.chunk_end, 105);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn chunks_exhausted_sans_end() {
+        let start = 0;
+        let chunk_length = 10;
+        let end = None;
+        let bytes = "0";
+
+        let mut chunks = Chunks::new(start, chunk_length, end, bytes);
+        for _ in 0..100 { chunks.advance(); }
+
+        assert!(!chunks.exhausted());
+    }
+}
@@ -0,0 +1,181 @@
+use serde::{
+    Deserialize,
+    Serialize
+};
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::mods::{
+    Modifier,
+    Page,
+    restored
+};
+
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    current: Option<String>,
+    index: u64
+}
+
+pub const NAME: &str = "cursor";
+
+/// Cursor paginates using a token found in the response body at a JSON Pointer
+pub struct Cursor {
+    pointer: String,
+    start: Option<String>,
+    current: Option<String>,
+    next: Option<String>,
+    index: u64
+}
+
+impl Cursor {
+    pub fn new(pointer: &str, start: Option<String>) -> Self {
+        Cursor {
+            pointer: String::from(pointer),
+            current: start.clone(),
+            start,
+            next: None,
+            index: 0
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Modifier for Cursor {
+    fn key(&self) -> &'static str { NAME }
+
+    /// Modify cursor patterns in target string
+    ///
+    /// Replaces:  {:cursor:next}  {:cursor:index}  {:cursor:page}
+    /// With:      <token>         <index>          <index + 1>
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+        match params.first() {
+            Some(&"next") => Ok(Some(self.current.clone().unwrap_or_default())),
+            Some(&"index") => Ok(Some(format!("{}", self.index))),
+            Some(&"page") => Ok(Some(format!("{}", self.index + 1))),
+            _ => Ok(None)
+        }
+    }
+
+    fn advance(&mut self) {
+        self.current = self.next.take();
+        self.index += 1;
+    }
+
+    fn reset(&mut self) {
+        self.current = self.start.clone();
+        self.next = None;
+        self.index = 0;
+    }
+
+    fn exhausted(&self) -> bool {
+        self.index > 0 && self.current.is_none()
+    }
+
+    fn iterates(&self) -> bool { true }
+
+    fn checkpoint(&self) -> Option<Value> {
+        serde_json::to_value(Checkpoint { current: self.current.clone(), index: self.index }).ok()
+    }
+
+    fn restore(&mut self, state: &Value) -> Result<(), Error> {
+        let checkpoint: Checkpoint = restored(NAME, state)?;
+        self.current = checkpoint.current;
+        self.index = checkpoint.index;
+        Ok(())
+    }
+
+    fn requires_body(&self) -> bool { true }
+
+    fn paginates(&self) -> bool { true }
+
+    fn observe(&mut self, page: &Page) {
+        let token = page.body.and_then(|body| body.pointer(&self.pointer));
+        self.next = match token {
+            Some(Value::String(token)) if !token.is_empty() => Some(token.clone()),
+            Some(Value::Number(token)) => Some(token.to_string()),
+            _ => None
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hyper::HeaderMap;
+    use serde_json::json;
+
+    fn observe(cursor: &mut Cursor, body: Option<&Value>) {
+        let headers = HeaderMap::new();
+        cursor.observe(&Page { uri: "https://example.com", headers: &headers, body });
+    }
+
+    #[test]
+    fn cursor_modify_params() {
+        let mut cursor = Cursor::new("/next", Some(String::from("first")));
+
+        assert_eq!(cursor.modify(vec!["next"]).unwrap(), Some(String::from("first")));
+        assert_eq!(cursor.modify(vec!["index"]).unwrap(), Some(String::from("0")));
+        assert_eq!(cursor.modify(vec!["page"]).unwrap(), Some(String::from("1")));
+        assert_eq!(cursor.modify(vec!["unknown"]).unwrap(), None);
+    }
+
+    #[test]
+    fn cursor_sans_start() {
+        let mut cursor = Cursor::new("/next", None);
+
+        assert_eq!(cursor.modify(vec!["next"]).unwrap(), Some(String::new()));
+        assert!(!cursor.exhausted());
+    }
+
+    #[test]
+    fn cursor_follows_tokens_until_absent() {
+        let mut cursor = Cursor::new("/links/next", None);
+
+        observe(&mut cursor, Some(&json!({ "links": { "next": "abc" } })));
+        cursor.advance();
+        assert!(!cursor.exhausted());
+        assert_eq!(cursor.modify(vec!["next"]).unwrap(), Some(String::from("abc")));
+
+        observe(&mut cursor, Some(&json!({ "links": { "next": 42 } })));
+        cursor.advance();
+        assert_eq!(cursor.modify(vec!["next"]).unwrap(), Some(String::from("42")));
+
+        observe(&mut cursor, Some(&json!({ "links": {} })));
+        cursor.advance();
+        assert!(cursor.exhausted());
+    }
+
+    #[test]
+    fn cursor_reset_to_start() {
+        let mut cursor = Cursor::new("/next", Some(String::from("first")));
+
+        observe(&mut cursor, Some(&json!({ "next": "second" })));
+        cursor.advance();
+        cursor.reset();
+
+        assert_eq!(cursor.modify(vec!["next"]).unwrap(), Some(String::from("first")));
+        assert_eq!(cursor.modify(vec!["index"]).unwrap(), Some(String::from("0")));
+    }
+
+    #[test]
+    fn cursor_exhausted_on_empty_token() {
+        let mut cursor = Cursor::new("/next_cursor", None);
+
+        observe(&mut cursor, Some(&json!({ "next_cursor": "" })));
+        cursor.advance();
+
+        assert!(cursor.exhausted());
+    }
+
+    #[test]
+    fn cursor_exhausted_sans_body() {
+        let mut cursor = Cursor::new("/next_cursor", None);
+
+        observe(&mut cursor, None);
+        cursor.advance();
+
+        assert!(cursor.exhausted());
+    }
+}
@@ -0,0 +1,178 @@
+use chrono::{
+    DateTime,
+    Duration,
+    Utc
+};
+use chrono::format::{
+    Item,
+    StrftimeItems
+};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde_json::{
+    json,
+    Value
+};
+
+use crate::error::Error;
+use crate::mods::{
+    Modifier,
+    restored
+};
+
+pub const NAME: &str = "datetime";
+
+/// RFC 3339 (used when no format is given)
+const DEFAULT_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%:z";
+
+/// Datetime formats run start time (pinned once per run) in timezone with optional offset
+pub struct Datetime {
+    now: DateTime<Utc>,
+    timezone: Tz
+}
+
+impl Datetime {
+    pub fn new(now: DateTime<Utc>, timezone: Tz) -> Self {
+        Datetime { now, timezone }
+    }
+}
+
+/// Offset of time expression from now (`now`, `now-1d`, `now+2h`)
+///
+/// Units: `s` seconds, `m` minutes, `h` hours, `d` days, `w` weeks (None when out of range)
+pub fn to_offset(expression: &str) -> Option<Duration> {
+    let pattern = Regex::new(r"^now(?:([+-])([0-9]+)([smhdw]))?$").unwrap();
+    let captures = pattern.captures(expression)?;
+    let (sign, amount, unit) = match (captures.get(1), captures.get(2), captures.get(3)) {
+        (Some(sign), Some(amount), Some(unit)) => (sign.as_str(), amount.as_str(), unit.as_str()),
+        _ => return Some(Duration::zero())
+    };
+    let amount: i64 = amount.parse().ok()?;
+    let amount = if sign == "-" { -amount } else { amount };
+    to_duration(amount, unit)
+}
+
+/// Duration of amount of unit (`s`, `m`, `h`, `d` or `w`) or None when out of range
+pub fn to_duration(amount: i64, unit: &str) -> Option<Duration> {
+    let unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return None
+    };
+    // building a duration beyond its bounds panics
+    let seconds = amount.checked_mul(unit)?;
+    if seconds.abs() > Duration::max_value().num_seconds() {
+        return None;
+    }
+    Some(Duration::seconds(seconds))
+}
+
+/// Strftime format is valid (formatting an invalid format panics)
+pub fn is_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| item == Item::Error)
+}
+
+#[async_trait::async_trait]
+impl Modifier for Datetime {
+    fn key(&self) -> &'static str { NAME }
+
+    /// Modify datetime patterns in target string
+    ///
+    /// Replaces:  {:datetime}  {:datetime:<format>}  {:datetime:<expression>}  {:datetime:<expression>:<format>}
+    /// With:      run start time (plus expression offset) in timezone using strftime format (default RFC 3339)
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+        let (expression, format) = match params.as_slice() {
+            [] => ("now", DEFAULT_FORMAT),
+            [param] if param.starts_with("now") => (*param, DEFAULT_FORMAT),
+            [format] => ("now", *format),
+            [expression, format, ..] => (*expression, *format)
+        };
+
+        let offset = to_offset(expression)
+            .ok_or_else(|| Error::modifier(NAME, format!("invalid time expression \"{}\"", expression)))?;
+        if !is_format(format) {
+            return Err(Error::modifier(NAME, format!("invalid format \"{}\"", format)));
+        }
+
+        let time = self.now.checked_add_signed(offset)
+            .ok_or_else(|| Error::modifier(NAME, format!("time expression \"{}\" out of range", expression)))?
+            .with_timezone(&self.timezone);
+        Ok(Some(time.format(format).to_string()))
+    }
+
+    /// Run start time is kept so resumed runs write to the same partitions
+    fn checkpoint(&self) -> Option<Value> { Some(json!({ "now": self.now.to_rfc3339() })) }
+
+    fn restore(&mut self, state: &Value) -> Result<(), Error> {
+        let now: String = restored(NAME, &state["now"])?;
+        let now = DateTime::parse_from_rfc3339(&now).map_err(|e| Error::modifier(NAME, e))?;
+        self.now = now.with_timezone(&Utc);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(timezone: Tz) -> Datetime {
+        let now = DateTime::parse_from_rfc3339("2026-10-18T07:30:00Z").unwrap().with_timezone(&Utc);
+        Datetime::new(now, timezone)
+    }
+
+    #[test]
+    fn datetime_modify_formats() {
+        let mut datetime = datetime(Tz::UTC);
+
+        assert_eq!(datetime.modify(vec![]).unwrap(), Some(String::from("2026-10-18T07:30:00+00:00")));
+        assert_eq!(datetime.modify(vec!["%Y-%m-%d"]).unwrap(), Some(String::from("2026-10-18")));
+        assert_eq!(datetime.modify(vec!["%H"]).unwrap(), Some(String::from("07")));
+    }
+
+    #[test]
+    fn datetime_modify_offsets() {
+        let mut datetime = datetime(Tz::UTC);
+
+        assert_eq!(datetime.modify(vec!["now-1d", "%Y/%m/%d"]).unwrap(), Some(String::from("2026/10/17")));
+        assert_eq!(datetime.modify(vec!["now+1h", "%H"]).unwrap(), Some(String::from("08")));
+        assert_eq!(datetime.modify(vec!["now-2w"]).unwrap(), Some(String::from("2026-10-04T07:30:00+00:00")));
+    }
+
+    #[tokio::test]
+    async fn datetime_reduce_format_with_colons() {
+        let mut mods = crate::mods::Modifiers::new(vec![Box::new(datetime(Tz::UTC))]);
+
+        let actual = mods.reduce(String::from("{:datetime:%H\\:%M} {:datetime:now+1h:%H\\:%M\\:%S}")).await.unwrap();
+
+        assert_eq!(actual, "07:30 08:30:00");
+    }
+
+    #[test]
+    fn datetime_modify_timezone() {
+        let mut datetime = datetime(Tz::Asia__Tokyo);
+
+        assert_eq!(datetime.modify(vec!["%Y-%m-%d %H"]).unwrap(), Some(String::from("2026-10-18 16")));
+    }
+
+    #[test]
+    fn datetime_modify_invalid() {
+        let mut datetime = datetime(Tz::UTC);
+
+        assert!(datetime.modify(vec!["now-1y", "%Y"]).is_err());
+        assert!(datetime.modify(vec!["%Q"]).is_err());
+    }
+
+    #[test]
+    fn datetime_modify_out_of_range() {
+        let mut datetime = datetime(Tz::UTC);
+
+        assert_eq!(to_offset("now+99999999999999w"), None);
+        assert_eq!(to_offset("now-9223372036854775807s"), None);
+        assert!(datetime.modify(vec!["now+99999999999999w"]).is_err());
+        assert!(datetime.modify(vec!["now+99999999999d", "%Y"]).is_err());
+        assert!(datetime.modify(vec!["now-99999999999999999999s"]).is_err());
+    }
+}
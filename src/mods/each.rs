@@ -0,0 +1,154 @@
+use serde_json::{
+    json,
+    Value
+};
+
+use crate::adapters::location;
+use crate::error::Error;
+use crate::event::Location;
+use crate::mods::{
+    Modifier,
+    restored
+};
+
+pub const NAME: &str = "each";
+
+/// Load values array from JSON object (or file) at pointer (default root)
+pub async fn load_values(from: &Location, pointer: Option<&str>) -> Result<Vec<Value>, Error> {
+    let bytes = location::read(from).await?.ok_or_else(|| Error::modifier(NAME, format!("{} not found in {}", from.name, from.collection)))?;
+    let json: Value = serde_json::from_slice(&bytes)
+        .map_err(|e| Error::modifier(NAME, format!("{}: {}", from.name, e)))?;
+    to_values(json, pointer).ok_or_else(|| {
+        Error::modifier(NAME, format!("{}: expected array at \"{}\"", from.name, pointer.unwrap_or("")))
+    })
+}
+
+fn to_values(mut json: Value, pointer: Option<&str>) -> Option<Vec<Value>> {
+    match json.pointer_mut(pointer.unwrap_or(""))?.take() {
+        Value::Array(values) => Some(values),
+        _ => None
+    }
+}
+
+/// Value as placeholder replacement (strings unquoted, other values as JSON)
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string()
+    }
+}
+
+/// Each iterates over a list of values (one value per chunk)
+pub struct Each {
+    values: Vec<Value>,
+    index: usize
+}
+
+impl Each {
+    pub fn new(values: Vec<Value>) -> Self {
+        Each { values, index: 0 }
+    }
+}
+
+#[async_trait::async_trait]
+impl Modifier for Each {
+    fn key(&self) -> &'static str { NAME }
+
+    /// Modify each patterns in target string
+    ///
+    /// Replaces:  {:each:value}  {:each:value:<pointer>}    {:each:index}  {:each:page}
+    /// With:      <value>        <value at JSON Pointer>    <index>        <index + 1>
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+        match params.first() {
+            Some(&"value") => {
+                let value = match (self.values.get(self.index), params.get(1)) {
+                    (Some(value), Some(pointer)) => value.pointer(pointer),
+                    (value, _) => value
+                };
+                Ok(value.map(to_string))
+            }
+            Some(&"index") => Ok(Some(format!("{}", self.index))),
+            Some(&"page") => Ok(Some(format!("{}", self.index + 1))),
+            _ => Ok(None)
+        }
+    }
+
+    fn advance(&mut self) {
+        self.index += 1;
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+
+    fn exhausted(&self) -> bool {
+        self.index >= self.values.len()
+    }
+
+    fn iterates(&self) -> bool { true }
+
+    fn checkpoint(&self) -> Option<Value> { Some(json!({ "index": self.index })) }
+
+    fn restore(&mut self, state: &Value) -> Result<(), Error> {
+        let index: usize = restored(NAME, &state["index"])?;
+        self.index = index;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::Path;
+
+    use crate::adapters::temp_directory;
+    use crate::event::Kind;
+
+    #[test]
+    fn each_modify_params() {
+        let mut each = Each::new(vec![json!("acme"), json!(42), json!({ "id": "x-1" })]);
+
+        assert_eq!(each.modify(vec!["value"]).unwrap(), Some(String::from("acme")));
+        assert_eq!(each.modify(vec!["index"]).unwrap(), Some(String::from("0")));
+        each.advance();
+        assert_eq!(each.modify(vec!["value"]).unwrap(), Some(String::from("42")));
+        assert_eq!(each.modify(vec!["page"]).unwrap(), Some(String::from("2")));
+        each.advance();
+        assert_eq!(each.modify(vec!["value", "/id"]).unwrap(), Some(String::from("x-1")));
+        assert!(!each.exhausted());
+        each.advance();
+        assert!(each.exhausted());
+    }
+
+    #[test]
+    fn each_sans_values_exhausted() {
+        assert!(Each::new(vec![]).exhausted());
+    }
+
+    #[test]
+    fn to_values_at_pointer() {
+        let json = json!({ "data": { "ids": [1, 2] } });
+
+        assert_eq!(to_values(json.clone(), Some("/data/ids")), Some(vec![json!(1), json!(2)]));
+        assert_eq!(to_values(json.clone(), Some("/data")), None);
+        assert_eq!(to_values(json!(["a"]), None), Some(vec![json!("a")]));
+    }
+
+    #[tokio::test]
+    async fn load_values_from_file() {
+        let directory = temp_directory();
+        std::fs::create_dir_all(directory.path()).unwrap();
+        std::fs::write(Path::new(directory.path()).join("ids.json"), r#"{ "ids": ["a", "b"] }"#).unwrap();
+        let from = Location {
+            kind: Kind::File,
+            region: String::new(),
+            endpoint: None,
+            collection: String::from(directory.path()),
+            name: String::from("ids.json")
+        };
+
+        assert_eq!(load_values(&from, Some("/ids")).await.unwrap(), vec![json!("a"), json!("b")]);
+        assert!(load_values(&from, Some("/missing")).await.is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use http::Uri;
+use hyper::HeaderMap;
+use regex::Regex;
+use serde::{
+    Deserialize,
+    Serialize
+};
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::mods::{
+    Modifier,
+    Page,
+    restored
+};
+
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    current: Option<String>,
+    index: u64
+}
+
+pub const NAME: &str = "link";
+
+/// Link paginates by following `rel="next"` in the response Link header (RFC 8288)
+pub struct Link {
+    current: Option<String>,
+    next: Option<String>,
+    index: u64
+}
+
+impl Link {
+    pub fn new() -> Self {
+        Link {
+            current: None,
+            next: None,
+            index: 0
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Modifier for Link {
+    fn key(&self) -> &'static str { NAME }
+
+    /// Modify link patterns in target string
+    ///
+    /// Replaces:  {:link:index}  {:link:page}
+    /// With:      <index>        <index + 1>
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+        match params.first() {
+            Some(&"index") => Ok(Some(format!("{}", self.index))),
+            Some(&"page") => Ok(Some(format!("{}", self.index + 1))),
+            _ => Ok(None)
+        }
+    }
+
+    fn advance(&mut self) {
+        self.current = self.next.take();
+        self.index += 1;
+    }
+
+    fn reset(&mut self) {
+        *self = Link::new();
+    }
+
+    fn exhausted(&self) -> bool {
+        self.index > 0 && self.current.is_none()
+    }
+
+    fn iterates(&self) -> bool { true }
+
+    fn checkpoint(&self) -> Option<Value> {
+        serde_json::to_value(Checkpoint { current: self.current.clone(), index: self.index }).ok()
+    }
+
+    fn restore(&mut self, state: &Value) -> Result<(), Error> {
+        let checkpoint: Checkpoint = restored(NAME, state)?;
+        self.current = checkpoint.current;
+        self.index = checkpoint.index;
+        Ok(())
+    }
+
+    fn uri(&self) -> Option<String> { self.current.clone() }
+
+    fn paginates(&self) -> bool { true }
+
+    fn observe(&mut self, page: &Page) {
+        self.next = next_link(page.headers, page.uri);
+    }
+}
+
+/// Find `rel="next"` target within Link headers (relative targets are resolved against request uri)
+fn next_link(headers: &HeaderMap, uri: &str) -> Option<String> {
+    let links = Regex::new(r"<([^>]*)>([^<]*)").unwrap();
+    let rel = Regex::new(r#"(?i)\brel\s*=\s*"?([^";,]+)"?"#).unwrap();
+    let target = headers
+        .get_all("link")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| links.captures_iter(value))
+        .find(|link| {
+            rel.captures(&link[2])
+                .map(|rel| rel[1].split_whitespace().any(|r| r.eq_ignore_ascii_case("next")))
+                .unwrap_or(false)
+        })
+        .map(|link| String::from(link[1].trim()))?;
+
+    if target.is_empty() { return None; }
+    if target.contains("://") { return Some(target); }
+
+    let uri: Uri = uri.parse().ok()?;
+    let origin = format!("{}://{}", uri.scheme_str()?, uri.authority()?);
+    if target.starts_with('/') {
+        return Some(format!("{}{}", origin, target));
+    }
+    let path = uri.path();
+    let base = &path[..path.rfind('/').map(|i| i + 1).unwrap_or(0)];
+    Some(format!("{}{}{}", origin, base, target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hyper::header::HeaderValue;
+
+    const URI: &str = "https://api.example.com/repos/issues?page=1";
+
+    fn headers(link: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("link", HeaderValue::from_str(link).unwrap());
+        headers
+    }
+
+    #[test]
+    fn link_modify_params() {
+        let mut link = Link::new();
+
+        assert_eq!(link.modify(vec!["index"]).unwrap(), Some(String::from("0")));
+        assert_eq!(link.modify(vec!["page"]).unwrap(), Some(String::from("1")));
+        assert_eq!(link.modify(vec!["unknown"]).unwrap(), None);
+        assert_eq!(link.uri(), None);
+    }
+
+    #[test]
+    fn link_next_link() {
+        let headers = headers(concat!(
+            "<https://api.example.com/repos/issues?page=1>; rel=\"prev\", ",
+            "<https://api.example.com/repos/issues?page=3&a=b,c>; rel=\"next\", ",
+            "<https://api.example.com/repos/issues?page=9>; rel=\"last\""
+        ));
+
+        let expect = Some(String::from("https://api.example.com/repos/issues?page=3&a=b,c"));
+        let actual = next_link(&headers, URI);
+
+        assert_eq!(actual, expect);
+    }
+
+    #[test]
+    fn link_next_link_relative() {
+        assert_eq!(
+            next_link(&headers("</repos/issues?page=2>; rel=next"), URI),
+            Some(String::from("https://api.example.com/repos/issues?page=2"))
+        );
+        assert_eq!(
+            next_link(&headers("<issues?page=2>; title=\"x\"; rel=\"next last\""), URI),
+            Some(String::from("https://api.example.com/repos/issues?page=2"))
+        );
+    }
+
+    #[test]
+    fn link_next_link_absent() {
+        assert_eq!(next_link(&headers("<https://api.example.com/?page=1>; rel=\"first\""), URI), None);
+        assert_eq!(next_link(&HeaderMap::new(), URI), None);
+    }
+
+    #[test]
+    fn link_follows_next_until_absent() {
+        let mut link = Link::new();
+
+        let next = headers("<https://api.example.com/repos/issues?page=2>; rel=\"next\"");
+        link.observe(&Page { uri: URI, headers: &next, body: None });
+        link.advance();
+        assert!(!link.exhausted());
+        assert_eq!(link.uri(), Some(String::from("https://api.example.com/repos/issues?page=2")));
+        assert_eq!(link.modify(vec!["page"]).unwrap(), Some(String::from("2")));
+
+        link.observe(&Page { uri: URI, headers: &HeaderMap::new(), body: None });
+        link.advance();
+        assert!(link.exhausted());
+    }
+}
@@ -1,18 +1,54 @@
 mod chunks;
+mod cursor;
+mod datetime;
+mod each;
+mod link;
 mod secrets;
+mod state;
 mod uuid;
+mod window;
 
+use chrono::{
+    DateTime,
+    Utc
+};
+use chrono_tz::Tz;
+use futures::future::{
+    BoxFuture,
+    FutureExt
+};
+use hyper::HeaderMap;
 use regex::Regex;
+use serde::de::DeserializeOwned;
 use serde_json::{
+    json,
     Map,
-    Value,
+    Value
+};
+
+pub use datetime::is_format;
+pub use state::State;
+pub use window::{
+    to_step,
+    to_time
 };
 
 use crate::adapters::secrets::get_secret;
+use crate::error::Error;
+use crate::event::ModConfig;
 
-type Mod = Box<dyn Modifier + Send>;
+pub type Mod = Box<dyn Modifier + Send>;
 type Mods = Vec<Mod>;
 
+/// Page is a source response observed by modifiers that paginate using responses
+pub struct Page<'a> {
+    /// Request uri (secrets unredacted)
+    pub uri: &'a str,
+    pub headers: &'a HeaderMap,
+    /// Response body parsed as JSON (when required by a modifier and valid)
+    pub body: Option<&'a Value>
+}
+
 /// Modifier is able to modify a target of type T
 #[async_trait::async_trait]
 pub trait Modifier {
@@ -20,9 +56,65 @@ pub trait Modifier {
 
     fn option(&self, _: &str) -> Option<String> { None }
 
-    fn modify(&mut self, params: Vec<&str>) -> Option<String>;
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error>;
 
     fn advance(&mut self) { }
+
+    /// Restart iteration from the first value (inner loop of nested modifiers)
+    fn reset(&mut self) { }
+
+    /// Modifier has no more values to iterate (ends chunk loop)
+    fn exhausted(&self) -> bool { false }
+
+    /// Values produced by modifier that must not be reported (e.g. secrets)
+    fn sensitive(&self) -> Vec<String> { vec![] }
+
+    /// Modifier iterates over chunks (requests)
+    fn iterates(&self) -> bool { false }
+
+    /// Modifier observes response body (body is buffered and parsed as JSON)
+    fn requires_body(&self) -> bool { false }
+
+    /// Observe response page before advancing
+    fn observe(&mut self, _: &Page) { }
+
+    /// Modifier takes its next value from observed responses (e.g. cursor tokens)
+    fn paginates(&self) -> bool { false }
+
+    /// Uri requested in place of event source (e.g. next page link)
+    fn uri(&self) -> Option<String> { None }
+
+    /// State of modifier to continue an interrupted run (None when there's nothing to restore)
+    fn checkpoint(&self) -> Option<Value> { None }
+
+    /// Restore state of a checkpoint
+    fn restore(&mut self, _: &Value) -> Result<(), Error> { Ok(()) }
+}
+
+/// Parameters of a captured pattern (`{:name:key:sub-key}` has params `key` and `sub-key`)
+///
+/// `\:` is a literal `:` within a param (e.g. `{:datetime:%H\:%M}`)
+fn to_params(captured: &str) -> Vec<String> {
+    let mut params = vec![];
+    let mut param = String::new();
+    let mut chars = captured.trim_start_matches('{').trim_end_matches('}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&':') => {
+                param.push(':');
+                chars.next();
+            }
+            ':' => if !param.is_empty() { params.push(std::mem::take(&mut param)); },
+            c => param.push(c)
+        }
+    }
+    if !param.is_empty() { params.push(param); }
+    params.into_iter().skip(1).collect()
+}
+
+/// Deserialize checkpoint state of modifier
+fn restored<T: DeserializeOwned>(name: &str, state: &Value) -> Result<T, Error> {
+    serde_json::from_value(state.clone()).map_err(|e| Error::modifier(name, format!("invalid checkpoint: {}", e)))
 }
 
 /// Modifiers is a collection of structs that implement the Modifier trait
@@ -30,128 +122,214 @@ pub trait Modifier {
 /// Calling `reduce` performs the equivalent of a fold() on modifiers returning
 /// the result of modifier.modify() on each fold
 pub struct Modifiers {
-    mods: Mods
+    mods: Mods,
+    nested: bool
 }
 
 impl Modifiers {
+    /// Modifiers iterating in lockstep (every iterating modifier advances each chunk)
     pub fn new(mods: Mods) -> Self {
-        Modifiers { mods }
+        Modifiers { mods, nested: false }
+    }
+
+    /// Modifiers iterating as nested loops in configured order (first is the outer loop)
+    ///
+    /// The inner loop advances each chunk; once exhausted it's reset and the next outer loop advances
+    pub fn nested(mods: Mods) -> Self {
+        Modifiers { mods, nested: true }
     }
 
     pub fn find(&self, key: &str) -> Option<&Mod> {
         self.mods.iter().find(|m| { key == m.key() })
     }
 
-    pub async fn reduce(&mut self, target: String) -> String {
-        if self.mods.is_empty() { return target; }
+    pub async fn reduce(&mut self, target: String) -> Result<String, Error> {
+        if self.mods.is_empty() { return Ok(target); }
 
-        let mut res = target.clone();
-        // iterate over modifiers applying modify on res (clone of target string)
+        let mut res = target;
+        // iterate over modifiers applying modify on res
         for m in self.mods.iter_mut() {
-            let mut modified = String::new();
-
             // find modifier matches {:name:key:sub-key}
             let pattern = format!("\\{{:{}[^}}]*}}", m.key());
             let re = Regex::new(&pattern).unwrap();
-            let mut capture_matches = re.captures_iter(&res);
-
-            // get first capture; if empty continue or try next modifier
-            let mut next_capture = capture_matches.next();
-            if next_capture.is_none() { continue; }
-
-            // set capture start and end char position tracking
-            let mut capture_params = vec![];
-            let mut capture_start = 0;
-            let mut capture_end = 0;
-
-            // iterate over target characters
-            for (i, c) in res.chars().enumerate() {
-                // check if current character position is end of a capture position (get next capture)
-                if i == capture_end {
-                    // after first pass, next_capture is refreshed
-                    if i > 0 { next_capture = capture_matches.next(); }
-
-                    // if next capture has value, collect capture params
-                    if let Some(next_captures) = &next_capture {
-                        // collect modifier parameters
-                        let captured_pattern = next_captures.get(0).unwrap().as_str();
-                        let param_matches = Regex::new(r"([^:^{^}]+)").unwrap();
-                        let mut params = vec![];
-                        for (i, param) in param_matches.captures_iter(captured_pattern).enumerate() {
-                            if i > 0 { params.push(param.get(0).unwrap().as_str().clone()); }
-                        }
-                        capture_params = params;
-
-                        // update capture boundaries (start and end positions)
-                        let captures_match = next_captures.get(0).unwrap().range();
-                        capture_start = captures_match.start;
-                        capture_end = captures_match.end;
-                    }
+            if !re.is_match(&res) { continue; }
+
+            // copy text between matches (byte offsets) and push modified value of each match
+            let mut modified = String::with_capacity(res.len());
+            let mut last = 0;
+            for capture in re.find_iter(&res) {
+                modified.push_str(&res[last..capture.start()]);
+                let params = to_params(capture.as_str());
+                if let Some(result) = m.modify(params.iter().map(String::as_str).collect())? {
+                    modified.push_str(result.as_str());
                 }
+                last = capture.end();
+            }
+            modified.push_str(&res[last..]);
 
-                // check if current character position is start of a capture position (modify + push)
-                if i == capture_start {
-                    if let Some(result) = m.modify(capture_params.clone()) {
-                        modified.push_str(result.as_str());
-                    }
-                    continue;
-                }
+            res = modified;
+        }
+        Ok(res)
+    }
 
-                // if not inside capture boundary (start and end position) push character
-                if i < capture_start || i >= capture_end { modified.push(c); }
+    /// Reduce every string within a JSON value (object keys are not modified)
+    pub fn reduce_value(&mut self, target: Value) -> BoxFuture<'_, Result<Value, Error>> {
+        async move {
+            match target {
+                Value::String(s) => Ok(Value::String(self.reduce(s).await?)),
+                Value::Array(values) => {
+                    let mut reduced = Vec::with_capacity(values.len());
+                    for value in values { reduced.push(self.reduce_value(value).await?); }
+                    Ok(Value::Array(reduced))
+                }
+                Value::Object(values) => {
+                    let mut reduced = Map::new();
+                    for (key, value) in values { reduced.insert(key, self.reduce_value(value).await?); }
+                    Ok(Value::Object(reduced))
+                }
+                value => Ok(value)
             }
+        }.boxed()
+    }
 
-            res = modified;
+    /// Advance modifiers returning true when an inner loop was reset
+    pub fn advance(&mut self) -> bool {
+        if !self.nested {
+            for m in self.mods.iter_mut() { m.advance(); }
+            return false;
         }
-        res
+        let loops = self.loops();
+        match loops.len().checked_sub(1) {
+            Some(inner) => self.advance_loop(&loops, inner),
+            _ => false
+        }
+    }
+
+    /// End inner loop early (e.g. page signalled last page) advancing the next outer loop
+    ///
+    /// Returns false when modifiers aren't nested or there's no outer loop (the run ends)
+    pub fn end_loop(&mut self) -> bool {
+        let loops = self.loops();
+        if !self.nested || loops.len() < 2 { return false; }
+        let inner = loops.len() - 1;
+        self.mods[loops[inner]].reset();
+        self.advance_loop(&loops, inner - 1);
+        true
+    }
+
+    /// Indices of iterating modifiers (outer to inner)
+    fn loops(&self) -> Vec<usize> {
+        (0..self.mods.len()).filter(|i| self.mods[*i].iterates()).collect()
+    }
+
+    /// Advance loop at depth resetting it and advancing outer loops when exhausted (outermost loop is left exhausted)
+    fn advance_loop(&mut self, loops: &[usize], depth: usize) -> bool {
+        let mut reset = false;
+        for depth in (0..=depth).rev() {
+            let m = &mut self.mods[loops[depth]];
+            m.advance();
+            if depth == 0 || !m.exhausted() { break; }
+            m.reset();
+            reset = true;
+        }
+        reset
+    }
+
+    pub fn exhausted(&self) -> bool {
+        self.mods.iter().any(|m| m.exhausted())
+    }
+
+    pub fn sensitive(&self) -> Vec<String> {
+        self.mods.iter().flat_map(|m| m.sensitive()).collect()
+    }
+
+    pub fn iterates(&self) -> bool {
+        self.mods.iter().any(|m| m.iterates())
+    }
+
+    pub fn requires_body(&self) -> bool {
+        self.mods.iter().any(|m| m.requires_body())
+    }
+
+    pub fn observe(&mut self, page: &Page) {
+        for m in self.mods.iter_mut() { m.observe(page); }
+    }
+
+    pub fn paginates(&self) -> bool {
+        self.mods.iter().any(|m| m.paginates())
+    }
+
+    pub fn uri(&self) -> Option<String> {
+        self.mods.iter().find_map(|m| m.uri())
+    }
+
+    /// State of every modifier (by position) to continue an interrupted run
+    pub fn checkpoint(&self) -> Value {
+        self.mods.iter().map(|m| json!({ "name": m.key(), "state": m.checkpoint() })).collect()
     }
 
-    pub fn advance(&mut self) {
-        for m in self.mods.iter_mut() { m.advance(); }
+    /// Restore state of every modifier from checkpoint (modifiers must match those checkpointed)
+    pub fn restore(&mut self, checkpoint: &Value) -> Result<(), Error> {
+        let saved = checkpoint.as_array().cloned().unwrap_or_default();
+        let keys: Vec<&str> = saved.iter().map(|s| s["name"].as_str().unwrap_or_default()).collect();
+        if keys != self.mods.iter().map(|m| m.key()).collect::<Vec<&str>>() {
+            return Err(Error::modifier("checkpoint", format!("checkpoint of mods {:?} doesn't match event", keys)));
+        }
+        for (m, saved) in self.mods.iter_mut().zip(saved.iter()) {
+            match &saved["state"] {
+                Value::Null => {}
+                state => m.restore(state)?
+            }
+        }
+        Ok(())
     }
 }
 
-fn load(config: &Map<String, Value>) -> Box<dyn Modifier + Send> {
-    let name = config.get("name").unwrap();
-    match name.as_str().unwrap() {
-        chunks::NAME => {
-            let start = config.get("start").unwrap().as_u64().unwrap();
-            let chunk_length = config
-                .get("chunk")
-                .unwrap()
-                .get("length")
-                .unwrap()
-                .as_u64()
-                .unwrap();
-            let end = match config.get("end") {
-                Some(end) => end.as_u64(),
-                _ => None
+async fn load(config: &ModConfig, now: DateTime<Utc>) -> Result<Mod, Error> {
+    let m: Mod = match config {
+        ModConfig::Chunks { start, end, chunk, bytes } => {
+            let bytes = format!("{}", bytes);
+            Box::new(chunks::Chunks::new(*start, chunk.length, *end, bytes.as_str()))
+        },
+        ModConfig::Cursor { pointer, start } => {
+            Box::new(cursor::Cursor::new(pointer, start.clone()))
+        },
+        ModConfig::Each { values, from, pointer } => {
+            let values = match from {
+                Some(from) => each::load_values(from, pointer.as_deref()).await?,
+                _ => values.clone().unwrap_or_default()
             };
-            let bytes = format!("{}", config.get("bytes").unwrap());
-            Box::new(chunks::Chunks::new(start, chunk_length, end, bytes.as_str()))
+            Box::new(each::Each::new(values))
+        },
+        ModConfig::Datetime { timezone } => {
+            let timezone = timezone.as_deref().unwrap_or("UTC").parse().unwrap_or(Tz::UTC);
+            Box::new(datetime::Datetime::new(now, timezone))
         },
-        secrets::NAME => {
-            let region = config.get("region").unwrap().as_str().unwrap();
+        ModConfig::Link => Box::new(link::Link::new()),
+        ModConfig::Secrets { region } => {
             Box::new(secrets::Secrets::new(region, get_secret))
         },
-        uuid::NAME => Box::new(uuid::Uuid::new()),
-        _ => panic!(format!("modifier \"{}\" not found", name.as_str().unwrap()))
-    }
-}
-
-/// Convert vector of serde json Value to Mods required by Modifiers struct
-pub fn to_mods(config: Option<&Vec<Value>>) -> Mods {
-    let mut modifiers = vec![];
-
-    if let Some(config) = config {
-        for modifier in config {
-            modifiers.push(
-            load(modifier.as_object().unwrap())
-            )
+        ModConfig::Uuid => Box::new(uuid::Uuid::new()),
+        ModConfig::Window { start, end, step, format, timezone } => {
+            // validated with event (see `event::Validator`)
+            let start = window::to_time(start, now).unwrap_or(now);
+            let end = end.as_deref().and_then(|end| window::to_time(end, now)).unwrap_or(now);
+            let step = window::to_step(step).unwrap_or(window::Step::Months(1));
+            let timezone = timezone.as_deref().unwrap_or("UTC").parse().unwrap_or(Tz::UTC);
+            Box::new(window::Window::new(start, end, step, format.as_deref(), timezone))
         }
-    }
+    };
+    Ok(m)
+}
 
-    modifiers
+/// Convert event modifier configs to Mods required by Modifiers struct
+///
+/// Time-based modifiers (`datetime`, `window`) share the run start time `now` so placeholders agree. Modifiers
+/// iterating over stored lists (`each`) load them before the first request
+pub async fn to_mods(config: &[ModConfig], now: DateTime<Utc>) -> Result<Mods, Error> {
+    let mut mods = vec![];
+    for config in config { mods.push(load(config, now).await?); }
+    Ok(mods)
 }
 
 #[cfg(test)]
@@ -166,7 +344,7 @@ mod tests {
         let target = String::from("original");
 
         let expected = String::from("original");
-        let actual = mods.reduce(target).await;
+        let actual = mods.reduce(target).await.unwrap();
 
         assert_eq!(actual, expected);
     }
@@ -177,8 +355,8 @@ mod tests {
         #[async_trait::async_trait]
         impl Modifier for ModifierMock {
             fn key(&self) -> &'static str { "modifier-mock" }
-            fn modify(&mut self, params: Vec<&str>) -> Option<String> {
-                Some(format!("{} {}", params[0], params[1]))
+            fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+                Ok(Some(format!("{} {}", params[0], params[1])))
             }
         }
 
@@ -188,7 +366,7 @@ mod tests {
         let target = String::from("no matches in this string");
 
         let expected = target.clone();
-        let actual = mods.reduce(target).await;
+        let actual = mods.reduce(target).await.unwrap();
 
         assert_eq!(actual, expected);
     }
@@ -199,8 +377,8 @@ mod tests {
         #[async_trait::async_trait]
         impl Modifier for ModifierMock {
             fn key(&self) -> &'static str { "modifier-mock" }
-            fn modify(&mut self, params: Vec<&str>) -> Option<String> {
-                Some(format!("{} {}", params[0], params[1]))
+            fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+                Ok(Some(format!("{} {}", params[0], params[1])))
             }
         }
 
@@ -210,7 +388,7 @@ mod tests {
         let target = String::from("{:modifier-mock:key:value}");
 
         let expected = String::from("key value");
-        let actual = mods.reduce(target).await;
+        let actual = mods.reduce(target).await.unwrap();
 
         assert_eq!(actual, expected);
     }
@@ -221,8 +399,8 @@ mod tests {
         #[async_trait::async_trait]
         impl Modifier for ModifierMock {
             fn key(&self) -> &'static str { "modifier-mock" }
-            fn modify(&mut self, _: Vec<&str>) -> Option<String> {
-                Some(String::from("modified"))
+            fn modify(&mut self, _: Vec<&str>) -> Result<Option<String>, Error> {
+                Ok(Some(String::from("modified")))
             }
         }
 
@@ -232,7 +410,7 @@ mod tests {
         let target = String::from("result: {:modifier-mock}");
 
         let expected = String::from("result: modified");
-        let actual = mods.reduce(target).await;
+        let actual = mods.reduce(target).await.unwrap();
 
         assert_eq!(actual, expected);
     }
@@ -243,8 +421,8 @@ mod tests {
         #[async_trait::async_trait]
         impl Modifier for ModifierMock {
             fn key(&self) -> &'static str { "modifier-mock" }
-            fn modify(&mut self, params: Vec<&str>) -> Option<String> {
-                Some(format!("{}|{}", params[0], params[1]))
+            fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+                Ok(Some(format!("{}|{}", params[0], params[1])))
             }
         }
 
@@ -254,7 +432,7 @@ mod tests {
         let target = String::from("?a={:modifier-mock:key:alpha}&b={:modifier-mock:key:bravo}");
 
         let expected = String::from("?a=key|alpha&b=key|bravo");
-        let actual = mods.reduce(target).await;
+        let actual = mods.reduce(target).await.unwrap();
 
         assert_eq!(actual, expected);
     }
@@ -266,15 +444,15 @@ mod tests {
         #[async_trait::async_trait]
         impl Modifier for ModifierMock {
             fn key(&self) -> &'static str { "modifier-mock" }
-            fn modify(&mut self, params: Vec<&str>) -> Option<String> {
-                Some(format!("{{:chained-modifier-mock:{}:{}}}", params[0], params[1]))
+            fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+                Ok(Some(format!("{{:chained-modifier-mock:{}:{}}}", params[0], params[1])))
             }
         }
         #[async_trait::async_trait]
         impl Modifier for ChainedModifierMock {
             fn key(&self) -> &'static str { "chained-modifier-mock" }
-            fn modify(&mut self, params: Vec<&str>) -> Option<String> {
-                Some(format!("{} {}", params[0], params[1]))
+            fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+                Ok(Some(format!("{} {}", params[0], params[1])))
             }
         }
 
@@ -287,8 +465,152 @@ mod tests {
         let target = String::from("{:modifier-mock:key:value}");
 
         let expected = String::from("key value");
-        let actual = mods.reduce(target).await;
+        let actual = mods.reduce(target).await.unwrap();
 
         assert_eq!(actual, expected);
     }
-}
\ No newline at end of file
+
+    struct CounterMock {
+        key: &'static str,
+        index: u64,
+        end: u64
+    }
+
+    #[async_trait::async_trait]
+    impl Modifier for CounterMock {
+        fn key(&self) -> &'static str { self.key }
+        fn modify(&mut self, _: Vec<&str>) -> Result<Option<String>, Error> {
+            Ok(Some(format!("{}", self.index)))
+        }
+        fn advance(&mut self) { self.index += 1; }
+        fn reset(&mut self) { self.index = 0; }
+        fn exhausted(&self) -> bool { self.index >= self.end }
+        fn iterates(&self) -> bool { true }
+    }
+
+    fn counters(ends: &[u64]) -> Mods {
+        let keys = ["outer", "inner"];
+        ends.iter().zip(keys.iter()).map(|(end, key)| -> Mod {
+            Box::new(CounterMock { key, index: 0, end: *end })
+        }).collect()
+    }
+
+    #[tokio::test]
+    async fn modifiers_nested_advance() {
+        let mut mods = Modifiers::nested(counters(&[2, 3]));
+
+        let mut actual = vec![];
+        let mut resets = 0;
+        while !mods.exhausted() {
+            actual.push(mods.reduce(String::from("{:outer}-{:inner}")).await.unwrap());
+            if mods.advance() { resets += 1; }
+        }
+
+        let expect = vec!["0-0", "0-1", "0-2", "1-0", "1-1", "1-2"];
+        assert_eq!(actual, expect);
+        assert_eq!(resets, 2);
+    }
+
+    #[tokio::test]
+    async fn modifiers_nested_end_loop() {
+        let mut mods = Modifiers::nested(counters(&[2, 3]));
+
+        assert!(mods.end_loop());
+        assert_eq!(mods.reduce(String::from("{:outer}-{:inner}")).await.unwrap(), "1-0");
+        assert!(mods.end_loop());
+        assert!(mods.exhausted());
+
+        assert!(!Modifiers::new(counters(&[2, 3])).end_loop());
+    }
+
+    #[tokio::test]
+    async fn modifiers_checkpoint_restore() {
+        let mut mods = Modifiers::new(to_mods(&[
+            ModConfig::Each { values: Some(vec![json!("a"), json!("b")]), from: None, pointer: None },
+            ModConfig::Uuid
+        ], Utc::now()).await.unwrap());
+        mods.advance();
+        let checkpoint = mods.checkpoint();
+
+        let mut restored = Modifiers::new(to_mods(&[
+            ModConfig::Each { values: Some(vec![json!("a"), json!("b")]), from: None, pointer: None },
+            ModConfig::Uuid
+        ], Utc::now()).await.unwrap());
+        restored.restore(&checkpoint).unwrap();
+        assert_eq!(restored.reduce(String::from("{:each:value}")).await.unwrap(), "b");
+
+        let mut other = Modifiers::new(to_mods(&[ModConfig::Uuid], Utc::now()).await.unwrap());
+        assert!(other.restore(&checkpoint).is_err());
+    }
+
+    #[test]
+    fn to_params_escaped_colon() {
+        assert_eq!(to_params("{:chunks:chunk:index}"), vec!["chunk", "index"]);
+        assert_eq!(to_params("{:datetime:now-1d:%H\\:%M}"), vec!["now-1d", "%H:%M"]);
+        assert!(to_params("{:uuid}").is_empty());
+    }
+
+    #[tokio::test]
+    async fn to_mods_share_run_start_time() {
+        let now = DateTime::parse_from_rfc3339("2026-10-18T23:59:59Z").unwrap().with_timezone(&Utc);
+        let window = ModConfig::Window {
+            start: String::from("now-1d"),
+            end: None,
+            step: String::from("1d"),
+            format: Some(String::from("%Y-%m-%d")),
+            timezone: None
+        };
+        let mut mods = Modifiers::new(to_mods(&[ModConfig::Datetime { timezone: None }, window], now).await.unwrap());
+
+        let expect = "2026-10-18T23:59:59+00:00 2026-10-17 2026-10-18";
+        let actual = mods.reduce(String::from("{:datetime} {:window:start} {:window:end}")).await.unwrap();
+
+        assert_eq!(actual, expect);
+    }
+
+    #[tokio::test]
+    async fn modifiers_reduce_non_ascii() {
+        let mut mods = Modifiers::new(to_mods(&[ModConfig::Each {
+            values: Some(vec![json!("Zürich")]),
+            from: None,
+            pointer: None
+        }], Utc::now()).await.unwrap());
+
+        let target = json!({ "query": "städte → {:each:value} ✓ {:each:index}" });
+
+        let expect = json!({ "query": "städte → Zürich ✓ 0" });
+        let actual = mods.reduce_value(target).await.unwrap();
+
+        assert_eq!(actual, expect);
+    }
+
+    #[tokio::test]
+    async fn modifiers_reduce_value() {
+        struct ModifierMock {}
+        #[async_trait::async_trait]
+        impl Modifier for ModifierMock {
+            fn key(&self) -> &'static str { "modifier-mock" }
+            fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+                Ok(Some(format!("{} \"{}\"", params[0], params[1])))
+            }
+        }
+
+        let config: Mods = vec![Box::new(ModifierMock {})];
+        let mut mods = Modifiers::new(config);
+
+        let target = serde_json::json!({
+            "{:modifier-mock:key:name}": "{:modifier-mock:key:value}",
+            "list": ["{:modifier-mock:a:b}", 1, null],
+            "nested": { "flag": true }
+        });
+
+        let expect = serde_json::json!({
+            "{:modifier-mock:key:name}": "key \"value\"",
+            "list": ["a \"b\"", 1, null],
+            "nested": { "flag": true }
+        });
+        let actual = mods.reduce_value(target).await.unwrap();
+
+        assert_eq!(actual, expect);
+    }
+}
@@ -4,6 +4,7 @@ use std::future::Future;
 use serde_json::Value;
 use futures::executor::block_on;
 
+use crate::error::Error;
 use crate::mods::Modifier;
 
 pub const NAME: &str = "secrets";
@@ -12,7 +13,7 @@ pub const NAME: &str = "secrets";
 pub struct Secrets<F, Fut>
 where
     F: Fn(String, String, Option<String>, Option<String>) -> Fut + Send,
-    Fut: Future<Output = Option<String>>
+    Fut: Future<Output = Result<Option<String>, Error>>
 {
     region: String,
     cache: HashMap<String, Option<String>>,
@@ -22,7 +23,7 @@ where
 impl<F, Fut> Secrets<F, Fut>
 where
     F: Fn(String, String, Option<String>, Option<String>) -> Fut + Send,
-    Fut: Future<Output = Option<String>>
+    Fut: Future<Output = Result<Option<String>, Error>>
 {
     pub fn new(region: &str, fetcher: F) -> Self {
         Secrets {
@@ -35,27 +36,33 @@ where
     /// Get secret by name and key
     ///
     /// Uses cache or async service call
-    fn get(&mut self, n: &str, k: &str) -> Option<String> {
+    fn get(&mut self, n: &str, k: &str) -> Result<Option<String>, Error> {
         let cache_key = format!("{}:{}", n, k);
 
-        if let Some(s) = self.cache.get_mut(cache_key.as_str()) { return s.clone(); }
+        if let Some(s) = self.cache.get_mut(cache_key.as_str()) { return Ok(s.clone()); }
 
-        if let Some(s) = block_on(self.fetch(String::from(n))) {
-            let secret: Value = serde_json::from_str(s.as_str()).unwrap();
-            for (key, value) in secret.as_object().unwrap().iter() {
+        if let Some(s) = block_on(self.fetch(String::from(n)))? {
+            let secret: Value = serde_json::from_str(s.as_str())
+                .map_err(|e| Error::secrets(format!("{}: {}", n, e)))?;
+            let secret = secret
+                .as_object()
+                .ok_or_else(|| Error::secrets(format!("{}: expected JSON object", n)))?;
+            for (key, value) in secret.iter() {
                 let cache_key = format!("{}:{}", &n, &key);
-                let value = Some(String::from(value.as_str().unwrap()));
-                self.cache.insert(cache_key, value);
+                let value = value
+                    .as_str()
+                    .ok_or_else(|| Error::secrets(format!("{}: expected string value for \"{}\"", n, key)))?;
+                self.cache.insert(cache_key, Some(String::from(value)));
             }
-            return self.cache.get(format!("{}:{}", n, k).as_str()).unwrap().clone()
+            if let Some(s) = self.cache.get(cache_key.as_str()) { return Ok(s.clone()); }
         }
 
         self.cache.insert(cache_key, None);
-        None
+        Ok(None)
     }
 
     /// Fetch secret using secrets adapter
-    async fn fetch(&self, n: String) -> Option<String> {
+    async fn fetch(&self, n: String) -> Result<Option<String>, Error> {
         (self.fetcher)(self.region.clone(), n, None, None).await
     }
 }
@@ -64,7 +71,7 @@ where
 impl<F, Fut> Modifier for Secrets<F, Fut>
 where
     F: Fn(String, String, Option<String>, Option<String>) -> Fut + Send,
-    Fut: Future<Output = Option<String>>
+    Fut: Future<Output = Result<Option<String>, Error>>
 {
     fn key(&self) -> &'static str { NAME }
 
@@ -72,8 +79,15 @@ where
     ///
     /// Replaces:  {:secrets:<key>}
     /// With:      <value-for-key>
-    fn modify(&mut self, params: Vec<&str>) -> Option<String> {
-        self.get(params[0], params[1])
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+        match (params.first(), params.get(1)) {
+            (Some(name), Some(key)) => self.get(name, key),
+            _ => Err(Error::modifier(NAME, "missing secret name or key (e.g. {:secrets:<name>:<key>})"))
+        }
+    }
+
+    fn sensitive(&self) -> Vec<String> {
+        self.cache.values().filter_map(|s| s.clone()).collect()
     }
 }
 
@@ -88,13 +102,13 @@ mod tests {
         let cache_key = format!("{}:{}", &n, &k);
         let expects = Some(String::from("value"));
 
-        async fn fetcher(_: String, _: String, _: Option<String>, _: Option<String>) -> Option<String> {
-            Some(String::from("{\"key\":\"value\"}"))
+        async fn fetcher(_: String, _: String, _: Option<String>, _: Option<String>) -> Result<Option<String>, Error> {
+            Ok(Some(String::from("{\"key\":\"value\"}")))
         };
 
         let mut secrets = Secrets::new("us-east-1", fetcher);
 
-        let actual = secrets.get(&n, &k);
+        let actual = secrets.get(&n, &k).unwrap();
 
         assert_eq!(actual, expects);
         // verify cached after fetch
@@ -108,14 +122,14 @@ mod tests {
         let cache_key = format!("{}:{}", &n, &k);
         let expects = Some(String::from("value"));
 
-        async fn fetcher(_: String, _: String, _: Option<String>, _: Option<String>) -> Option<String> {
-            Some(String::from("{\"key\":\"not-value\"}"))
+        async fn fetcher(_: String, _: String, _: Option<String>, _: Option<String>) -> Result<Option<String>, Error> {
+            Ok(Some(String::from("{\"key\":\"not-value\"}")))
         };
 
         let mut secrets = Secrets::new("us-east-1", fetcher);
         secrets.cache.insert(cache_key, expects.clone());
 
-        let actual = secrets.get(&n, &k);
+        let actual = secrets.get(&n, &k).unwrap();
 
         assert_eq!(actual, expects);
     }
@@ -126,14 +140,43 @@ mod tests {
         let k = String::from("key");
         let expects = None;
 
-        async fn fetcher(_: String, _: String, _: Option<String>, _: Option<String>) -> Option<String> {
-            None
+        async fn fetcher(_: String, _: String, _: Option<String>, _: Option<String>) -> Result<Option<String>, Error> {
+            Ok(None)
         };
 
         let mut secrets = Secrets::new("us-east-1", fetcher);
 
-        let actual = secrets.get(&n, &k);
+        let actual = secrets.get(&n, &k).unwrap();
 
         assert_eq!(actual, expects);
     }
+
+    #[tokio::test]
+    async fn secrets_modify_missing_params_returns_error() {
+        async fn fetcher(_: String, _: String, _: Option<String>, _: Option<String>) -> Result<Option<String>, Error> {
+            Ok(Some(String::from("{\"key\":\"value\"}")))
+        }
+
+        let mut secrets = Secrets::new("us-east-1", fetcher);
+
+        assert!(secrets.modify(vec![]).is_err());
+        assert!(secrets.modify(vec!["namespace"]).is_err());
+        assert_eq!(secrets.modify(vec!["namespace", "key"]).unwrap(), Some(String::from("value")));
+    }
+
+    #[tokio::test]
+    async fn secrets_get_invalid_secret_returns_error() {
+        let n = String::from("namespace");
+        let k = String::from("key");
+
+        async fn fetcher(_: String, _: String, _: Option<String>, _: Option<String>) -> Result<Option<String>, Error> {
+            Ok(Some(String::from("not-json")))
+        }
+
+        let mut secrets = Secrets::new("us-east-1", fetcher);
+
+        let actual = secrets.get(&n, &k);
+
+        assert!(actual.is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::mods::Modifier;
+
+pub const NAME: &str = "state";
+
+/// State provides values of job state persisted by previous runs (see `event::State`)
+pub struct State {
+    values: BTreeMap<String, String>
+}
+
+impl State {
+    pub fn new(values: BTreeMap<String, String>) -> Self {
+        State { values }
+    }
+}
+
+#[async_trait::async_trait]
+impl Modifier for State {
+    fn key(&self) -> &'static str { NAME }
+
+    /// Modify state patterns in target string
+    ///
+    /// Replaces:  {:state:<key>}
+    /// With:      <value-for-key>
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+        let key = params.first().copied().unwrap_or_default();
+        match self.values.get(key) {
+            Some(value) => Ok(Some(value.clone())),
+            _ => Err(Error::modifier(NAME, format!("no value for \"{}\" (see state.initial)", key)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_modify_key() {
+        let mut values = BTreeMap::new();
+        values.insert(String::from("since"), String::from("2021-01-01"));
+        let mut state = State::new(values);
+
+        assert_eq!(state.modify(vec!["since"]).unwrap(), Some(String::from("2021-01-01")));
+        assert!(state.modify(vec!["until"]).is_err());
+    }
+}
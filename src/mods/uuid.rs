@@ -1,8 +1,13 @@
 use std::collections::HashMap;
 
+use serde_json::Value;
 use uuid;
 
-use crate::mods::Modifier;
+use crate::error::Error;
+use crate::mods::{
+    Modifier,
+    restored
+};
 
 pub const NAME: &str = "uuid";
 
@@ -23,15 +28,23 @@ impl Uuid {
 impl Modifier for Uuid {
     fn key(&self) -> &'static str { NAME }
 
-    fn modify(&mut self, params: Vec<&str>) -> Option<String> {
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
         if let Some(key) = params.get(0) {
             let uuid = self.cache
                 .entry(String::from(*key))
                 .or_insert(uuid::Uuid::new_v4().to_string())
                 .clone();
-            return Some(uuid);
+            return Ok(Some(uuid));
         }
-        Some(uuid::Uuid::new_v4().to_string())
+        Ok(Some(uuid::Uuid::new_v4().to_string()))
+    }
+
+    /// Keyed uuids are kept so resumed runs reuse them
+    fn checkpoint(&self) -> Option<Value> { serde_json::to_value(&self.cache).ok() }
+
+    fn restore(&mut self, state: &Value) -> Result<(), Error> {
+        self.cache = restored(NAME, state)?;
+        Ok(())
     }
 }
 
@@ -48,7 +61,7 @@ mod tests {
         let params = vec![];
 
         let mut uuid = Uuid::new();
-        let actual = uuid.modify(params);
+        let actual = uuid.modify(params).unwrap();
 
         let uuid_v4 = Regex::new(UUID_V4_PATTERN).unwrap();
 
@@ -60,8 +73,8 @@ mod tests {
         let params = vec!["key"];
 
         let mut uuid = Uuid::new();
-        let actual_first = uuid.modify(params.clone());
-        let actual_second = uuid.modify(params.clone());
+        let actual_first = uuid.modify(params.clone()).unwrap();
+        let actual_second = uuid.modify(params.clone()).unwrap();
 
         let uuid_v4 = Regex::new(UUID_V4_PATTERN).unwrap();
 
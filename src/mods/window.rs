@@ -0,0 +1,283 @@
+use std::convert::TryFrom;
+
+use chrono::{
+    DateTime,
+    Datelike,
+    Duration,
+    NaiveDate,
+    TimeZone,
+    Utc
+};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde::{
+    Deserialize,
+    Serialize
+};
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::mods::{
+    Modifier,
+    restored
+};
+use crate::mods::datetime::{
+    is_format,
+    to_duration,
+    to_offset
+};
+
+pub const NAME: &str = "window";
+
+/// RFC 3339 (used when no format is given)
+const DEFAULT_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%:z";
+
+/// Range and index of window (range is pinned once per run when relative to now)
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    start: String,
+    end: String,
+    index: u64
+}
+
+/// Step between window starts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Duration(Duration),
+    Months(u32)
+}
+
+/// Parse time as RFC 3339, date (midnight UTC) or expression relative to now (`now-7d`)
+///
+/// None when invalid or out of range
+pub fn to_time(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?));
+    }
+    to_offset(value).and_then(|offset| now.checked_add_signed(offset))
+}
+
+/// Parse step (e.g. `30m`, `1h`, `1d`, `1w`, `1 month`, `1 year`) or None when invalid or out of range
+pub fn to_step(value: &str) -> Option<Step> {
+    let pattern = Regex::new(r"^([0-9]+)\s*(s|m|h|d|w|months?|years?)$").unwrap();
+    let captures = pattern.captures(value.trim())?;
+    let amount: i64 = captures[1].parse().ok()?;
+    if amount == 0 { return None; }
+    let step = match &captures[2] {
+        "month" | "months" => Step::Months(u32::try_from(amount).ok()?),
+        "year" | "years" => Step::Months(u32::try_from(amount).ok()?.checked_mul(12)?),
+        unit => Step::Duration(to_duration(amount, unit)?)
+    };
+    Some(step)
+}
+
+/// Add months to time (day is clamped to last day of month) or None when out of range
+fn add_months(time: DateTime<Utc>, months: u32) -> Option<DateTime<Utc>> {
+    let naive = time.naive_utc();
+    let total = (naive.year() * 12 + naive.month0() as i32).checked_add(i32::try_from(months).ok()?)?;
+    let (year, month) = (total.div_euclid(12), total.rem_euclid(12) as u32 + 1);
+    (1..=naive.day()).rev()
+        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+        .map(|date| Utc.from_utc_datetime(&date.and_time(naive.time())))
+}
+
+/// Window chunks a date/time range by step (last window ends at range end)
+pub struct Window {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    step: Step,
+    format: String,
+    timezone: Tz,
+    index: u64
+}
+
+impl Window {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>, step: Step, format: Option<&str>, timezone: Tz) -> Self {
+        Window {
+            start,
+            end,
+            step,
+            format: String::from(format.unwrap_or(DEFAULT_FORMAT)),
+            timezone,
+            index: 0
+        }
+    }
+
+    /// Start of window at index (computed from range start so months don't drift)
+    ///
+    /// Starts past the range end (including those out of the time range) are clamped to the range end
+    fn window_start(&self, index: u64) -> DateTime<Utc> {
+        let start = match self.step {
+            Step::Duration(step) => i64::try_from(index).ok()
+                .and_then(|index| step.num_milliseconds().checked_mul(index))
+                .and_then(|offset| self.start.checked_add_signed(Duration::milliseconds(offset))),
+            Step::Months(months) => u32::try_from(index).ok()
+                .and_then(|index| months.checked_mul(index))
+                .and_then(|months| add_months(self.start, months))
+        };
+        match start {
+            Some(start) if start <= self.end => start,
+            _ => self.end
+        }
+    }
+
+    fn format(&self, time: DateTime<Utc>, format: Option<&&str>) -> Result<Option<String>, Error> {
+        let format = format.copied().unwrap_or(&self.format);
+        if !is_format(format) {
+            return Err(Error::modifier(NAME, format!("invalid format \"{}\"", format)));
+        }
+        Ok(Some(time.with_timezone(&self.timezone).format(format).to_string()))
+    }
+}
+
+#[async_trait::async_trait]
+impl Modifier for Window {
+    fn key(&self) -> &'static str { NAME }
+
+    /// Modify window patterns in target string
+    ///
+    /// Replaces:  {:window:start}  {:window:end}  {:window:index}  {:window:page}
+    /// With:      <window start>   <window end>   <index>          <index + 1>
+    ///
+    /// Start and end accept a format overriding the configured format (e.g. `{:window:start:%Y-%m-%d}`)
+    fn modify(&mut self, params: Vec<&str>) -> Result<Option<String>, Error> {
+        match params.first() {
+            Some(&"start") => self.format(self.window_start(self.index), params.get(1)),
+            Some(&"end") => self.format(self.window_start(self.index + 1), params.get(1)),
+            Some(&"index") => Ok(Some(format!("{}", self.index))),
+            Some(&"page") => Ok(Some(format!("{}", self.index + 1))),
+            _ => Ok(None)
+        }
+    }
+
+    fn advance(&mut self) {
+        self.index += 1;
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+
+    fn exhausted(&self) -> bool {
+        self.window_start(self.index) >= self.end
+    }
+
+    fn iterates(&self) -> bool { true }
+
+    fn checkpoint(&self) -> Option<Value> {
+        serde_json::to_value(Checkpoint {
+            start: self.start.to_rfc3339(),
+            end: self.end.to_rfc3339(),
+            index: self.index
+        }).ok()
+    }
+
+    fn restore(&mut self, state: &Value) -> Result<(), Error> {
+        let checkpoint: Checkpoint = restored(NAME, state)?;
+        let now = self.start;
+        let time = |value: &str| to_time(value, now)
+            .ok_or_else(|| Error::modifier(NAME, format!("invalid checkpoint time \"{}\"", value)));
+        self.start = time(&checkpoint.start)?;
+        self.end = time(&checkpoint.end)?;
+        self.index = checkpoint.index;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    fn modify(window: &mut Window, param: &str) -> String {
+        window.modify(vec![param]).unwrap().unwrap()
+    }
+
+    #[test]
+    fn to_time_formats() {
+        let now = time("2026-10-18T07:30:00Z");
+
+        assert_eq!(to_time("2026-01-01T06:00:00+01:00", now), Some(time("2026-01-01T05:00:00Z")));
+        assert_eq!(to_time("2026-01-01", now), Some(time("2026-01-01T00:00:00Z")));
+        assert_eq!(to_time("now-1d", now), Some(time("2026-10-17T07:30:00Z")));
+        assert_eq!(to_time("yesterday", now), None);
+        assert_eq!(to_time("now+99999999d", now), None);
+        assert_eq!(to_time("now-9999999999999w", now), None);
+    }
+
+    #[test]
+    fn to_step_units() {
+        assert_eq!(to_step("1h"), Some(Step::Duration(Duration::hours(1))));
+        assert_eq!(to_step("15m"), Some(Step::Duration(Duration::minutes(15))));
+        assert_eq!(to_step("1 month"), Some(Step::Months(1)));
+        assert_eq!(to_step("2 years"), Some(Step::Months(24)));
+        assert_eq!(to_step("0d"), None);
+        assert_eq!(to_step("1 fortnight"), None);
+        assert_eq!(to_step("99999999999999d"), None);
+        assert_eq!(to_step("99999999999 months"), None);
+        assert_eq!(to_step("4294967295 years"), None);
+    }
+
+    #[test]
+    fn window_days() {
+        let step = to_step("1d").unwrap();
+        let mut window = Window::new(time("2026-01-01T00:00:00Z"), time("2026-01-02T12:00:00Z"), step, Some("%Y-%m-%d %H"), Tz::UTC);
+
+        assert_eq!(modify(&mut window, "start"), "2026-01-01 00");
+        assert_eq!(modify(&mut window, "end"), "2026-01-02 00");
+        assert_eq!(modify(&mut window, "index"), "0");
+
+        window.advance();
+        assert!(!window.exhausted());
+        assert_eq!(modify(&mut window, "start"), "2026-01-02 00");
+        assert_eq!(modify(&mut window, "end"), "2026-01-02 12");
+        assert_eq!(modify(&mut window, "page"), "2");
+
+        window.advance();
+        assert!(window.exhausted());
+    }
+
+    #[tokio::test]
+    async fn window_reduce_format_with_colons() {
+        let step = to_step("1h").unwrap();
+        let window = Window::new(time("2026-01-01T00:00:00Z"), time("2026-01-02T00:00:00Z"), step, Some("%H:%M"), Tz::UTC);
+        let mut mods = crate::mods::Modifiers::new(vec![Box::new(window)]);
+
+        let actual = mods.reduce(String::from("{:window:start}-{:window:end:%H\\:%M\\:%S}")).await.unwrap();
+
+        assert_eq!(actual, "00:00-01:00:00");
+    }
+
+    #[test]
+    fn window_months_clamp_day() {
+        let step = to_step("1 month").unwrap();
+        let mut window = Window::new(time("2026-01-31T00:00:00Z"), time("2026-06-01T00:00:00Z"), step, None, Tz::UTC);
+
+        window.advance();
+        assert_eq!(modify(&mut window, "start"), "2026-02-28T00:00:00+00:00");
+        assert_eq!(window.modify(vec!["end", "%Y-%m-%d"]).unwrap(), Some(String::from("2026-03-31")));
+    }
+
+    #[test]
+    fn window_huge_steps_clamp_to_end() {
+        let end = time("2026-06-01T00:00:00Z");
+        let mut days = Window::new(time("2026-01-01T00:00:00Z"), end, to_step("99999999999d").unwrap(), None, Tz::UTC);
+        let mut years = Window::new(time("2026-01-01T00:00:00Z"), end, to_step("300000 years").unwrap(), None, Tz::UTC);
+
+        assert_eq!(add_months(time("2026-01-31T00:00:00Z"), 3_600_000), None);
+        for window in [&mut days, &mut years].iter_mut() {
+            assert_eq!(modify(window, "end"), "2026-06-01T00:00:00+00:00");
+            window.advance();
+            assert!(window.exhausted());
+        }
+
+        days.index = u64::MAX;
+        assert_eq!(days.window_start(days.index), end);
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Mutex;
+use std::time::{
+    Duration,
+    Instant
+};
+
+use log::info;
+
+use crate::event::Rate;
+
+/// Token bucket shared by every request of a run (including retries and concurrent chunks)
+pub struct Limiter {
+    /// Tokens added per millisecond
+    refill: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>
+}
+
+struct Bucket {
+    /// Tokens available (negative when requests are waiting for reserved tokens)
+    tokens: f64,
+    updated: Instant
+}
+
+impl Limiter {
+    pub fn new(config: &Rate) -> Self {
+        let burst = f64::from(config.burst.unwrap_or(config.requests));
+        Limiter {
+            refill: f64::from(config.requests) / config.interval as f64,
+            burst,
+            bucket: Mutex::new(Bucket { tokens: burst, updated: Instant::now() })
+        }
+    }
+
+    /// Reserve a token returning delay until it's available
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let elapsed = now.saturating_duration_since(bucket.updated).as_millis() as f64;
+        bucket.tokens = (bucket.tokens + elapsed * self.refill).min(self.burst) - 1.0;
+        bucket.updated = now;
+        if bucket.tokens >= 0.0 { return Duration::default(); }
+        Duration::from_millis((-bucket.tokens / self.refill).ceil() as u64)
+    }
+
+    /// Wait for a token before sending a request
+    pub async fn acquire(&self) {
+        let delay = self.reserve(Instant::now());
+        if delay > Duration::default() {
+            info!("source rate limit reached; waiting {}ms", delay.as_millis());
+            tokio::time::delay_for(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests: u32, interval: u64, burst: Option<u32>) -> Limiter {
+        Limiter::new(&Rate { requests, interval, burst })
+    }
+
+    #[test]
+    fn limiter_allows_burst() {
+        let limiter = limiter(10, 1000, None);
+        let now = Instant::now();
+
+        for _ in 0..10 { assert_eq!(limiter.reserve(now), Duration::default()); }
+        assert_eq!(limiter.reserve(now), Duration::from_millis(100));
+        assert_eq!(limiter.reserve(now), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn limiter_refills_over_time() {
+        let limiter = limiter(1, 1000, Some(2));
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve(now), Duration::default());
+        assert_eq!(limiter.reserve(now), Duration::default());
+        assert_eq!(limiter.reserve(now), Duration::from_millis(1000));
+        // refill is capped at burst
+        assert_eq!(limiter.reserve(now + Duration::from_secs(60)), Duration::default());
+        assert_eq!(limiter.reserve(now + Duration::from_secs(60)), Duration::default());
+        assert_eq!(limiter.reserve(now + Duration::from_secs(60)), Duration::from_millis(1000));
+    }
+}
@@ -0,0 +1,243 @@
+use std::future::Future;
+use std::time::{
+    Duration,
+    SystemTime
+};
+
+use hyper::{
+    HeaderMap,
+    StatusCode
+};
+use log::{
+    info,
+    warn
+};
+use rand::Rng;
+
+use crate::adapters::http::Response;
+use crate::error::Error;
+use crate::event::Retry;
+
+/// Exponential backoff delay before retrying attempt (attempts count from 1)
+pub fn backoff(config: &Retry, attempt: u32) -> Duration {
+    let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+    let delay = config.delay.saturating_mul(factor).min(config.max_delay);
+    Duration::from_millis(delay)
+}
+
+/// Equal jitter - keeps half of delay and randomizes remainder
+fn jitter(delay: Duration) -> Duration {
+    let half = delay.as_millis() as u64 / 2;
+    let random = rand::thread_rng().gen_range(0..=half);
+    Duration::from_millis(half + random)
+}
+
+/// Parse Retry-After header as delay-seconds or HTTP-date
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get("retry-after")?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+/// Delay before next attempt or None when result is final
+fn next_delay(config: &Retry, attempt: u32, result: &Result<Response, Error>) -> Option<Duration> {
+    if attempt >= config.attempts { return None; }
+
+    let delay = backoff(config, attempt);
+    let delay = if config.jitter { jitter(delay) } else { delay };
+
+    match result {
+        Ok((status, headers, _)) if config.statuses.contains(&status.as_u16()) => {
+            match *status {
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+                    match retry_after(headers) {
+                        Some(after) if after > Duration::from_millis(config.max_delay) => {
+                            warn!("source Retry-After of {}s exceeds max_delay; not retrying", after.as_secs());
+                            None
+                        }
+                        Some(after) => Some(after),
+                        _ => Some(delay)
+                    }
+                }
+                _ => Some(delay)
+            }
+        }
+        Err(Error::Source { transport: Some(transport), .. }) if config.errors.contains(transport) => Some(delay),
+        _ => None
+    }
+}
+
+/// Send request retrying transient failures per policy
+///
+/// Returns final result and number of attempts made
+pub async fn send<F, Fut>(config: Option<&Retry>, mut request: F) -> (Result<Response, Error>, u32)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Response, Error>>
+{
+    let mut attempt = 1;
+    loop {
+        let result = request().await;
+        let config = match config {
+            Some(config) => config,
+            _ => return (result, attempt)
+        };
+
+        let delay = match next_delay(config, attempt, &result) {
+            Some(delay) => delay,
+            _ => return (result, attempt)
+        };
+        match &result {
+            Ok((status, _, _)) => warn!(
+                "source attempt {}/{} returned {}; retrying in {}ms",
+                attempt, config.attempts, status, delay.as_millis()
+            ),
+            Err(e) => warn!(
+                "source attempt {}/{} failed: {}; retrying in {}ms",
+                attempt, config.attempts, e, delay.as_millis()
+            )
+        }
+        drop(result);
+
+        tokio::time::delay_for(delay).await;
+        attempt += 1;
+        info!("source attempt {}/{}", attempt, config.attempts);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hyper::header::HeaderValue;
+
+    use crate::error::Transport;
+
+    fn config() -> Retry {
+        Retry {
+            attempts: 3,
+            delay: 100,
+            max_delay: 1000,
+            jitter: false,
+            statuses: vec![429, 503],
+            errors: vec![Transport::Connect]
+        }
+    }
+
+    fn response(status: StatusCode, headers: HeaderMap) -> Result<Response, Error> {
+        Ok((status, headers, Box::new(futures::stream::empty())))
+    }
+
+    #[test]
+    fn backoff_exponential_capped() {
+        let config = config();
+
+        assert_eq!(backoff(&config, 1), Duration::from_millis(100));
+        assert_eq!(backoff(&config, 2), Duration::from_millis(200));
+        assert_eq!(backoff(&config, 4), Duration::from_millis(800));
+        assert_eq!(backoff(&config, 5), Duration::from_millis(1000));
+        assert_eq!(backoff(&config, 100), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn jitter_within_bounds() {
+        let delay = Duration::from_millis(1000);
+        for _ in 0..100 {
+            let actual = jitter(delay);
+            assert!(actual >= Duration::from_millis(500) && actual <= delay);
+        }
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("3"));
+
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn retry_after_past_date() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(0)));
+    }
+
+    #[test]
+    fn next_delay_honours_retry_after() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("1"));
+
+        let actual = next_delay(&config(), 1, &response(StatusCode::TOO_MANY_REQUESTS, headers));
+
+        assert_eq!(actual, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn next_delay_retry_after_exceeds_max_delay() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("60"));
+
+        let actual = next_delay(&config(), 1, &response(StatusCode::SERVICE_UNAVAILABLE, headers));
+
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn next_delay_not_retryable() {
+        let config = config();
+
+        let ok = next_delay(&config, 1, &response(StatusCode::OK, HeaderMap::new()));
+        let status = next_delay(&config, 1, &response(StatusCode::INTERNAL_SERVER_ERROR, HeaderMap::new()));
+        let error = next_delay(&config, 1, &Err(Error::source("bad uri")));
+        let exhausted = next_delay(&config, 3, &response(StatusCode::TOO_MANY_REQUESTS, HeaderMap::new()));
+
+        assert_eq!(ok, None);
+        assert_eq!(status, None);
+        assert_eq!(error, None);
+        assert_eq!(exhausted, None);
+    }
+
+    #[test]
+    fn next_delay_transport_error() {
+        let error = Err(Error::Source {
+            message: String::from("refused"),
+            transport: Some(Transport::Connect),
+            status: None
+        });
+
+        let actual = next_delay(&config(), 2, &error);
+
+        assert_eq!(actual, Some(Duration::from_millis(200)));
+    }
+
+    #[tokio::test]
+    async fn send_retries_until_success() {
+        let mut config = config();
+        config.delay = 1;
+        let mut calls = 0;
+
+        let (result, attempts) = send(Some(&config), || {
+            calls += 1;
+            let status = if calls < 3 { StatusCode::TOO_MANY_REQUESTS } else { StatusCode::OK };
+            async move { response(status, HeaderMap::new()) }
+        }).await;
+
+        assert_eq!(result.unwrap().0, StatusCode::OK);
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn send_sans_policy_single_attempt() {
+        let (result, attempts) = send(None, || async {
+            response(StatusCode::SERVICE_UNAVAILABLE, HeaderMap::new())
+        }).await;
+
+        assert_eq!(result.unwrap().0, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(attempts, 1);
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use crate::adapters::location;
+use crate::error::Error;
+use crate::event::State;
+
+pub type Values = BTreeMap<String, String>;
+
+/// Load job state (initial values are overridden by persisted values)
+pub async fn load(config: &State) -> Result<Values, Error> {
+    let location = config.location();
+    let mut values = config.initial.clone();
+    if let Some(bytes) = location::read(&location).await? {
+        let persisted: Values = serde_json::from_slice(&bytes)
+            .map_err(|e| location::error(&location, format!("invalid state: {}", e)))?;
+        values.extend(persisted);
+    }
+    Ok(values)
+}
+
+/// Persist job state
+pub async fn save(config: &State, values: &Values) -> Result<(), Error> {
+    let location = config.location();
+    let body = serde_json::to_vec_pretty(values).map_err(|e| location::error(&location, e))?;
+    location::write(&location, "application/json", body.into()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::adapters::temp_directory;
+    use crate::event::Kind;
+
+    fn config(directory: &str) -> State {
+        let mut initial = Values::new();
+        initial.insert(String::from("since"), String::from("2021-01-01"));
+        initial.insert(String::from("cursor"), String::new());
+        State {
+            kind: Kind::File,
+            region: String::new(),
+            endpoint: None,
+            collection: String::from(directory),
+            job: String::from("orders"),
+            initial,
+            update: Values::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn state_load_initial_then_saved() {
+        let directory = temp_directory();
+        let config = config(directory.path());
+
+        assert_eq!(load(&config).await.unwrap(), config.initial);
+
+        let mut values = Values::new();
+        values.insert(String::from("since"), String::from("2021-02-01"));
+        save(&config, &values).await.unwrap();
+
+        let loaded = load(&config).await.unwrap();
+        assert_eq!(loaded.get("since"), Some(&String::from("2021-02-01")));
+        assert_eq!(loaded.get("cursor"), Some(&String::new()));
+    }
+
+    #[test]
+    fn state_error_kind_of_location() {
+        let mut config = config("bucket");
+
+        assert_eq!(location::error(&config.location(), "invalid").kind(), "destination_file");
+        config.kind = Kind::S3;
+        assert_eq!(location::error(&config.location(), "invalid").kind(), "destination_s3");
+    }
+}
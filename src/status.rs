@@ -0,0 +1,90 @@
+use hyper::StatusCode;
+
+use crate::event::{
+    Action,
+    Status
+};
+
+/// Action for response status or None when response is accepted
+///
+/// Precedence: exact code action, accepted codes (default 2xx), class action (`4xx`), default action
+pub fn action(config: Option<&Status>, status: StatusCode) -> Option<Action> {
+    let config = match config {
+        Some(config) => config,
+        _ => {
+            if status.is_success() { return None; }
+            return Some(Action::Fail);
+        }
+    };
+
+    let code = status.as_u16();
+    let class = format!("{}xx", code / 100);
+    if let Some(action) = config.actions.get(&code.to_string()) { return Some(*action); }
+
+    let accepted = match &config.accept {
+        Some(accept) => accept.contains(&code),
+        _ => status.is_success()
+    };
+    if accepted { return None; }
+
+    Some(*config.actions.get(&class).unwrap_or(&config.default))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    fn config() -> Status {
+        let mut actions = BTreeMap::new();
+        actions.insert(String::from("204"), Action::Stop);
+        actions.insert(String::from("404"), Action::Stop);
+        actions.insert(String::from("4xx"), Action::Skip);
+        Status {
+            accept: None,
+            actions,
+            default: Action::Store,
+            errors: Some(String::from("errors"))
+        }
+    }
+
+    #[test]
+    fn action_sans_config() {
+        assert_eq!(action(None, StatusCode::OK), None);
+        assert_eq!(action(None, StatusCode::NO_CONTENT), None);
+        assert_eq!(action(None, StatusCode::NOT_FOUND), Some(Action::Fail));
+        assert_eq!(action(None, StatusCode::INTERNAL_SERVER_ERROR), Some(Action::Fail));
+    }
+
+    #[test]
+    fn action_precedence() {
+        let config = config();
+
+        assert_eq!(action(Some(&config), StatusCode::OK), None);
+        assert_eq!(action(Some(&config), StatusCode::NO_CONTENT), Some(Action::Stop));
+        assert_eq!(action(Some(&config), StatusCode::NOT_FOUND), Some(Action::Stop));
+        assert_eq!(action(Some(&config), StatusCode::GONE), Some(Action::Skip));
+        assert_eq!(action(Some(&config), StatusCode::BAD_GATEWAY), Some(Action::Store));
+    }
+
+    #[test]
+    fn action_accept_list() {
+        let mut config = config();
+        config.accept = Some(vec![200, 302]);
+
+        assert_eq!(action(Some(&config), StatusCode::FOUND), None);
+        assert_eq!(action(Some(&config), StatusCode::CREATED), Some(Action::Store));
+    }
+
+    #[test]
+    fn action_accept_list_before_class() {
+        let mut config = config();
+        config.actions.remove("404");
+        config.accept = Some(vec![200, 404]);
+
+        assert_eq!(action(Some(&config), StatusCode::NOT_FOUND), None);
+        assert_eq!(action(Some(&config), StatusCode::GONE), Some(Action::Skip));
+        assert_eq!(action(Some(&config), StatusCode::NO_CONTENT), Some(Action::Stop));
+    }
+}
@@ -0,0 +1,121 @@
+use serde_json::Value;
+
+use crate::event::Until;
+
+/// Stop condition met by a response body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    /// Page has no items (page is not stored)
+    Empty,
+    /// Page has fewer items than page size
+    Size,
+    /// More flag is false
+    More,
+    /// Items seen across pages reached total count
+    Total
+}
+
+/// Stop condition met by response body or None when pagination continues
+///
+/// Items of the page are added to `seen`; bodies that aren't JSON meet no condition
+pub fn reached(config: &Until, body: Option<&Value>, seen: &mut u64) -> Option<Condition> {
+    let body = body?;
+
+    if let Some(count) = count(config, body) {
+        if count == 0 { return Some(Condition::Empty); }
+        *seen += count;
+        if let Some(size) = config.size {
+            if count < size { return Some(Condition::Size); }
+        }
+        let total = config.total.as_ref().and_then(|pointer| body.pointer(pointer)).and_then(to_u64);
+        if let Some(total) = total {
+            if *seen >= total { return Some(Condition::Total); }
+        }
+    }
+
+    let more = config.more.as_ref().and_then(|pointer| body.pointer(pointer));
+    if let Some(Value::Bool(false)) = more { return Some(Condition::More); }
+
+    None
+}
+
+/// Page has no items (page is not stored)
+pub fn is_empty(config: &Until, body: Option<&Value>) -> bool {
+    matches!(body.and_then(|body| count(config, body)), Some(0))
+}
+
+/// Count of page items or None when items isn't configured (or not an array)
+fn count(config: &Until, body: &Value) -> Option<u64> {
+    config.items.as_ref().and_then(|pointer| match body.pointer(pointer) {
+        Some(Value::Array(items)) => Some(items.len() as u64),
+        Some(Value::Null) | None => Some(0),
+        _ => None
+    })
+}
+
+/// Total counts are sometimes sent as strings
+fn to_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(value) => value.parse().ok(),
+        value => value.as_u64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn config() -> Until {
+        Until {
+            items: Some(String::from("/data")),
+            size: Some(2),
+            more: Some(String::from("/has_more")),
+            total: Some(String::from("/total"))
+        }
+    }
+
+    #[test]
+    fn reached_empty() {
+        let mut seen = 0;
+
+        assert_eq!(reached(&config(), Some(&json!({ "data": [] })), &mut seen), Some(Condition::Empty));
+        assert_eq!(reached(&config(), Some(&json!({ "data": null })), &mut seen), Some(Condition::Empty));
+        assert_eq!(reached(&config(), Some(&json!({})), &mut seen), Some(Condition::Empty));
+        assert_eq!(seen, 0);
+    }
+
+    #[test]
+    fn reached_size() {
+        let mut seen = 0;
+
+        assert_eq!(reached(&config(), Some(&json!({ "data": [1, 2] })), &mut seen), None);
+        assert_eq!(reached(&config(), Some(&json!({ "data": [3] })), &mut seen), Some(Condition::Size));
+        assert_eq!(seen, 3);
+    }
+
+    #[test]
+    fn reached_more() {
+        let mut seen = 0;
+        let body = json!({ "data": [1, 2], "has_more": false });
+
+        assert_eq!(reached(&config(), Some(&body), &mut seen), Some(Condition::More));
+    }
+
+    #[test]
+    fn reached_total() {
+        let mut seen = 0;
+
+        assert_eq!(reached(&config(), Some(&json!({ "data": [1, 2], "total": "4" })), &mut seen), None);
+        assert_eq!(reached(&config(), Some(&json!({ "data": [3, 4], "total": 4 })), &mut seen), Some(Condition::Total));
+    }
+
+    #[test]
+    fn reached_sans_json() {
+        let mut seen = 0;
+
+        assert_eq!(reached(&config(), None, &mut seen), None);
+        assert_eq!(reached(&config(), Some(&json!({ "data": "nope", "has_more": true })), &mut seen), None);
+    }
+}